@@ -0,0 +1,97 @@
+//! 解析吞吐量基准：对比旧的按 `String` 逐行读取方式与按字节解析的 `FastqReader`
+//!
+//! 运行：`cargo bench --bench throughput`
+
+use std::hint::black_box;
+use std::io::{BufRead, BufReader, Cursor};
+use std::time::{Duration, Instant};
+
+use check_fastq::{validate_record, validate_record_ref, FastqReader, FastqRecord};
+
+const RECORDS: usize = 200_000;
+const READ_LEN: usize = 150;
+const ROUNDS: usize = 5;
+
+/// 生成测试用的 FASTQ 数据
+fn synthetic_fastq() -> Vec<u8> {
+    let bases = b"ACGT";
+    let mut data = Vec::with_capacity(RECORDS * (READ_LEN * 2 + 40));
+    for i in 0..RECORDS {
+        data.extend_from_slice(format!("@SIM:1:FCX:1:1:{}:{} 1:N:0:ACGT\n", i % 5000, i).as_bytes());
+        data.extend((0..READ_LEN).map(|j| bases[(i + j) % 4]));
+        data.extend_from_slice(b"\n+\n");
+        data.extend((0..READ_LEN).map(|j| b'!' + ((i + j) % 41) as u8));
+        data.push(b'\n');
+    }
+    data
+}
+
+/// 旧实现：`BufReader::lines()`，每条记录分配 4 个 `String`
+fn parse_lines(data: &[u8]) -> usize {
+    let mut lines = BufReader::new(Cursor::new(data)).lines();
+    let mut line_num = 1;
+    let mut count = 0;
+    while let Some(Ok(header)) = lines.next() {
+        let record = FastqRecord {
+            header,
+            sequence: lines.next().unwrap().unwrap(),
+            plus_line: lines.next().unwrap().unwrap(),
+            quality: lines.next().unwrap().unwrap(),
+        };
+        if validate_record(&record, line_num).is_ok() {
+            count += 1;
+        }
+        line_num += 4;
+    }
+    count
+}
+
+/// 新实现：借用缓冲区的 `FastqRecordRef`
+fn parse_borrowed(data: &[u8]) -> usize {
+    let mut reader = FastqReader::new(Cursor::new(data));
+    let mut count = 0;
+    while reader.read_next().unwrap() {
+        if validate_record_ref(&reader.record(), reader.line_num()).is_ok() {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// 新实现的迭代器接口：每条记录复制为 `FastqRecord`
+fn parse_owned(data: &[u8]) -> usize {
+    let mut reader = FastqReader::new(Cursor::new(data));
+    let mut count = 0;
+    while let Some(record) = reader.next() {
+        if validate_record(&record.unwrap(), reader.line_num()).is_ok() {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn bench(name: &str, data: &[u8], parse: fn(&[u8]) -> usize) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        assert_eq!(black_box(parse(black_box(data))), RECORDS);
+        best = best.min(start.elapsed());
+    }
+
+    let mib = data.len() as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<28} {:>8.2} ms {:>10.1} MiB/s {:>12.0} 条/s",
+        name,
+        best.as_secs_f64() * 1000.0,
+        mib / best.as_secs_f64(),
+        RECORDS as f64 / best.as_secs_f64(),
+    );
+}
+
+fn main() {
+    let data = synthetic_fastq();
+    println!("{} 条记录, {} 字节, 取 {} 轮中的最好成绩", RECORDS, data.len(), ROUNDS);
+    bench("lines() + String (旧)", &data, parse_lines);
+    bench("FastqReader 借用视图", &data, parse_borrowed);
+    bench("FastqReader 迭代器", &data, parse_owned);
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::i18n::tr;
+
+/// 序列行允许使用的碱基字母表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alphabet {
+    /// 不检查序列内容
+    Any,
+    /// 仅 A、C、G、T、N
+    Strict,
+    /// 完整的 IUPAC 核酸代码（含 U）
+    #[default]
+    Iupac,
+    /// RNA：A、C、G、U、N
+    Rna,
+}
+
+impl Alphabet {
+    /// 碱基是否属于该字母表，`case_sensitive` 为 `false` 时同时接受小写
+    pub fn accepts(self, base: u8, case_sensitive: bool) -> bool {
+        let base = if case_sensitive { base } else { base.to_ascii_uppercase() };
+        match self {
+            Alphabet::Any => true,
+            Alphabet::Strict => matches!(base, b'A' | b'C' | b'G' | b'T' | b'N'),
+            Alphabet::Iupac => matches!(
+                base,
+                b'A' | b'C' | b'G' | b'T' | b'U' | b'R' | b'Y' | b'S' | b'W' | b'K' | b'M'
+                    | b'B' | b'D' | b'H' | b'V' | b'N'
+            ),
+            Alphabet::Rna => matches!(base, b'A' | b'C' | b'G' | b'U' | b'N'),
+        }
+    }
+
+    /// 序列中第一个不属于该字母表的字符的位置（从 0 开始）
+    pub fn first_invalid(self, sequence: &[u8], case_sensitive: bool) -> Option<usize> {
+        if self == Alphabet::Any {
+            return None;
+        }
+        sequence.iter().position(|&base| !self.accepts(base, case_sensitive))
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "any" => Ok(Alphabet::Any),
+            "strict" | "acgtn" => Ok(Alphabet::Strict),
+            "iupac" => Ok(Alphabet::Iupac),
+            "rna" => Ok(Alphabet::Rna),
+            _ => Err(tr!(
+                "未知的字母表: {}（可选 any、strict、iupac、rna）",
+                "unknown alphabet: {} (expected any, strict, iupac, rna)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Alphabet::Any => "any",
+            Alphabet::Strict => "strict",
+            Alphabet::Iupac => "iupac",
+            Alphabet::Rna => "rna",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_bases_of_each_alphabet() {
+        assert!(Alphabet::Strict.accepts(b'N', true));
+        assert!(!Alphabet::Strict.accepts(b'R', true));
+        assert!(Alphabet::Iupac.accepts(b'R', true));
+        assert!(!Alphabet::Iupac.accepts(b'X', true));
+        assert!(Alphabet::Rna.accepts(b'U', true));
+        assert!(!Alphabet::Rna.accepts(b'T', true));
+        assert!(Alphabet::Any.accepts(b'-', true));
+    }
+
+    #[test]
+    fn lowercase_depends_on_case_sensitivity() {
+        assert!(Alphabet::Strict.accepts(b'a', false));
+        assert!(!Alphabet::Strict.accepts(b'a', true));
+        assert_eq!(Alphabet::Strict.first_invalid(b"ACGTacgt", true), Some(4));
+        assert_eq!(Alphabet::Strict.first_invalid(b"ACGTacgt", false), None);
+    }
+
+    #[test]
+    fn parses_alphabet_names() {
+        assert_eq!("ACGTN".parse(), Ok(Alphabet::Strict));
+        assert_eq!("IUPAC".parse(), Ok(Alphabet::Iupac));
+        assert!("dna".parse::<Alphabet>().is_err());
+    }
+}
@@ -0,0 +1,556 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::duplicates::{DuplicateConfirmer, DuplicateTracker};
+use crate::header::IndexLengthTracker;
+use crate::i18n::tr;
+use crate::pair::{mate_name, read_number, MateTracker};
+use crate::report::ErrorReporter;
+use crate::{
+    validate_record_all, validate_record_with, Alphabet, DuplicateCheck, FastqError, FastqReader, FastqRecordRef,
+    Platform, QualityDetector, QualityEncoding, ReportFormat, Severity, Validator, Violation,
+};
+
+/// `check_fastq_file` 的检查选项
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    /// 记录错位（多出或缺少行）后向后搜索下一条完整记录并继续检查
+    pub resync: bool,
+    /// 序列行允许的碱基字母表
+    pub alphabet: Alphabet,
+    /// 碱基是否区分大小写（为 `true` 时小写碱基视为非法，否则只作为警告）
+    pub case_sensitive: bool,
+    /// 要求加号行只有一个 `+`，不重复标识符（为 `false` 时重复标识符只作为警告）
+    pub require_bare_plus: bool,
+    /// 质量值编码，`None` 表示只要求是可打印字符，并根据所有质量值推断编码
+    /// （推断结果见 `ValidationSummary::quality_encoding`，不影响检查）
+    pub quality_encoding: Option<QualityEncoding>,
+    /// 自动检测多数 index 长度时检查的记录数
+    pub detect_records: usize,
+    /// 重复读段名的检测方式
+    pub duplicates: DuplicateCheck,
+    /// 输入为交错格式，相邻两条记录是同一片段的两端
+    pub interleaved: bool,
+    /// 按测序平台的格式解析并验证标题行，`None` 表示不检查；
+    /// `Platform::Auto` 根据第一条记录识别平台，之后的记录都按该平台检查
+    pub platform: Option<Platform>,
+    /// 报告过滤标记为 `Y` 的读段
+    pub flag_filtered: bool,
+    /// 报告 index 序列长度与多数读段不同的读段
+    pub check_index_length: bool,
+    /// 错误输出的格式
+    pub report_format: ReportFormat,
+    /// `ReportFormat::Pretty` 的位置信息中显示的输入名称
+    pub source_name: Option<String>,
+    /// 成对检查时 `ReportFormat::Pretty` 的位置信息中 R1、R2 显示的输入名称 (R1, R2)，
+    /// 未设置时显示 `R1`、`R2`
+    pub pair_source_names: Option<(String, String)>,
+    /// `ReportFormat::Pretty` 是否使用 ANSI 颜色
+    pub color: bool,
+    /// 发现这么多错误后停止检查，`None` 表示检查整个输入；`Some(1)` 即遇到第一个错误就停止。
+    /// `Some(0)` 与 `None` 相同
+    pub max_errors: Option<usize>,
+    /// 每类错误最多写出的详细记录数，超过后只计数不再写入错误输出
+    pub max_errors_per_kind: Option<usize>,
+    /// 对每条记录执行的验证规则，默认为所有内置规则
+    pub validator: Validator,
+    /// 序列的最小长度，`None` 表示不检查
+    pub min_length: Option<usize>,
+    /// 序列的最大长度，`None` 表示不检查
+    pub max_length: Option<usize>,
+    /// 各类问题（键为 `FastqError::kind`）的严重程度，未列出的类别见 `severity_of`；
+    /// `Severity::Off` 的问题不计数也不报告
+    pub severity: BTreeMap<&'static str, Severity>,
+}
+
+impl CheckOptions {
+    /// 某类问题的严重程度
+    ///
+    /// 未在 `severity` 中列出时使用 `Severity::default_for`，但开启 `require_bare_plus` 后
+    /// `plus_line_not_bare` 为错误。
+    pub fn severity_of(&self, kind: &str) -> Severity {
+        if let Some(&severity) = self.severity.get(kind) {
+            return severity;
+        }
+        if kind == "plus_line_not_bare" && self.require_bare_plus {
+            return Severity::Error;
+        }
+        Severity::default_for(kind)
+    }
+
+    /// 已发现 `errors` 个错误时是否应停止检查
+    pub(crate) fn error_limit_reached(&self, errors: usize) -> bool {
+        self.max_errors.is_some_and(|max| max > 0 && errors >= max)
+    }
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            resync: false,
+            alphabet: Alphabet::default(),
+            case_sensitive: false,
+            require_bare_plus: false,
+            quality_encoding: None,
+            detect_records: 10_000,
+            duplicates: DuplicateCheck::Off,
+            interleaved: false,
+            platform: None,
+            flag_filtered: false,
+            check_index_length: false,
+            report_format: ReportFormat::Text,
+            source_name: None,
+            pair_source_names: None,
+            color: false,
+            max_errors: None,
+            max_errors_per_kind: None,
+            validator: Validator::new(),
+            min_length: None,
+            max_length: None,
+            severity: BTreeMap::new(),
+        }
+    }
+}
+
+/// 一次检查的统计结果
+#[derive(Debug, Clone, Default)]
+pub struct ValidationSummary {
+    /// 处理的记录总数
+    pub records: usize,
+    /// 所有记录的碱基总数
+    pub bases: u64,
+    /// 最短与最长的序列长度 (最短, 最长)，没有任何完整记录时为 `None`
+    pub read_length_range: Option<(usize, usize)>,
+    /// 错误数（严重程度为 `Severity::Error` 的问题），一条记录违反多条规则时分别计数
+    pub errors: usize,
+    /// 各类错误的数量，键为 `FastqError::kind`
+    pub errors_by_kind: BTreeMap<&'static str, usize>,
+    /// 警告数（严重程度为 `Severity::Warning` 或 `Severity::Info` 的问题），不影响检查结果
+    pub warnings: usize,
+    /// 各类警告的数量，键为 `FastqError::kind`
+    pub warnings_by_kind: BTreeMap<&'static str, usize>,
+    /// 行号最小的错误（不含警告），没有错误时为 `None`
+    pub first_error: Option<ErrorLocation>,
+    /// 读取的字节数（解压后）
+    pub bytes_read: u64,
+    /// 质量值编码：指定的编码，或未指定时根据所有质量值推断的编码，没有任何记录时为 `None`
+    pub quality_encoding: Option<QualityEncoding>,
+    /// 标题行所属的测序平台（指定的或自动识别的），未检查或无法识别时为 `None`
+    pub platform: Option<Platform>,
+    /// 达到 `CheckOptions::max_errors` 后提前停止，之后的记录没有检查
+    ///
+    /// 成对检查按两个文件的错误总数判断，两个文件的该字段相同。
+    pub stopped_early: bool,
+    /// 超过 `CheckOptions::max_errors_per_kind` 而没有写入错误输出的错误数
+    ///
+    /// 成对检查时见 `PairSummary::unreported_errors`。
+    pub unreported_errors: usize,
+    /// 检查用时
+    ///
+    /// 成对检查时为同时检查两个文件的总用时，两个文件的该字段相同。
+    pub elapsed: Duration,
+}
+
+/// 错误的类别与位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorLocation {
+    /// 错误代码，见 `FastqError::code`
+    pub code: &'static str,
+    /// 错误类别，见 `FastqError::kind`
+    pub kind: &'static str,
+    /// 出错的行号，见 `FastqError::line`
+    pub line: Option<usize>,
+}
+
+impl ValidationSummary {
+    /// 按严重程度把一个问题计为错误或警告，`Severity::Off` 的问题不计数
+    pub(crate) fn count(&mut self, err: &FastqError, severity: Severity) {
+        let (count, by_kind) = match severity {
+            Severity::Off => return,
+            Severity::Error => (&mut self.errors, &mut self.errors_by_kind),
+            Severity::Warning | Severity::Info => (&mut self.warnings, &mut self.warnings_by_kind),
+        };
+        *count += 1;
+        *by_kind.entry(err.kind()).or_default() += 1;
+
+        // 错误不一定按行号顺序发现（如孤立读段、Bloom 模式确认的重复），保留行号最小的
+        let earlier = match (self.first_error.and_then(|first| first.line), err.line()) {
+            (Some(first), Some(line)) => line < first,
+            (None, Some(_)) => true,
+            (_, None) => self.first_error.is_none(),
+        };
+        if severity == Severity::Error && earlier {
+            self.first_error = Some(ErrorLocation {
+                code: err.code(),
+                kind: err.kind(),
+                line: err.line(),
+            });
+        }
+    }
+
+    /// 统计一条完整记录的序列长度
+    fn observe_read(&mut self, length: usize) {
+        self.bases += length as u64;
+        self.read_length_range = Some(match self.read_length_range {
+            Some((min, max)) => (min.min(length), max.max(length)),
+            None => (length, length),
+        });
+    }
+}
+
+/// 解析 FASTQ 文件并验证其格式
+pub fn check_fastq_path<P: AsRef<Path>>(
+    input_path: P,
+    error_output_path: Option<P>,
+    options: &CheckOptions,
+) -> Result<ValidationSummary, FastqError> {
+    let input_path = input_path.as_ref();
+    let error_file = match error_output_path {
+        Some(path) => Some(File::create(path)?),
+        None => None,
+    };
+    check_fastq_reopenable(|| File::open(input_path), error_file, options)
+}
+
+/// 从任意输入流解析 FASTQ 并验证其格式，错误记录写入 `error_output`（如果提供）
+///
+/// 输入的压缩格式会被自动识别，因此可以直接传入标准输入或解压前的文件。
+/// 输入流只能读取一遍，Bloom 模式的重复检测在需要确认时会返回错误，请改用
+/// `check_fastq_path` 或 `check_fastq_reopenable`。
+pub fn check_fastq_file<R: Read + Send, W: Write>(
+    input: R,
+    error_output: Option<W>,
+    options: &CheckOptions,
+) -> Result<ValidationSummary, FastqError> {
+    let mut input = Some(input);
+    let open = move || {
+        input.take().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                tr!(
+                    "输入流无法重新读取，Bloom 模式的重复检测需要输入文件",
+                    "the input stream cannot be re-read; Bloom duplicate checking needs an input file",
+                ),
+            )
+        })
+    };
+    check_fastq_reopenable(open, error_output, options)
+}
+
+/// 与 `check_fastq_file` 相同，但输入通过 `open` 打开，需要时可以再读一遍
+///
+/// 目前只有 Bloom 模式的重复检测会第二次调用 `open`，用来确认候选的重复读段名。
+pub fn check_fastq_reopenable<R, W, F>(
+    mut open: F,
+    error_output: Option<W>,
+    options: &CheckOptions,
+) -> Result<ValidationSummary, FastqError>
+where
+    R: Read + Send,
+    W: Write,
+    F: FnMut() -> io::Result<R>,
+{
+    let start = Instant::now();
+
+    // 错误输出按块写入
+    let mut error_file = match error_output {
+        Some(out) => Some(ErrorReporter::new(BufWriter::new(out), options)?),
+        None => None,
+    };
+
+    let (mut summary, options, duplicates) = check_records(open()?, &mut error_file, options)?;
+
+    // 提前停止时候选的重复读段名不完整，不再确认
+    if !summary.stopped_early
+        && options.severity_of("duplicate_read_id") != Severity::Off
+        && let Some(confirmer) = duplicates.into_confirmer()
+    {
+        confirm_duplicates(open()?, &mut error_file, &options, confirmer, &mut summary)?;
+    }
+
+    if let Some(ref mut file) = error_file {
+        summary.unreported_errors = file.unreported();
+        file.flush()?;
+    }
+    summary.elapsed = start.elapsed();
+
+    Ok(summary)
+}
+
+/// 单个输入的逐条检查状态：统计、质量值编码检测与重复读段名跟踪
+pub(crate) struct RecordChecker {
+    options: CheckOptions,
+    detector: Option<QualityDetector>,
+    duplicates: DuplicateTracker,
+    summary: ValidationSummary,
+    crlf_reported: bool,
+}
+
+impl RecordChecker {
+    pub(crate) fn new(options: &CheckOptions) -> Self {
+        // 未指定质量值编码时，所有记录都只按可打印范围检查，推断出的编码只用于报告：
+        // 中途改用推断的编码会使前后的记录按不同的范围检查，推断有误时之后的记录都会报错
+        let detector = match options.quality_encoding {
+            Some(_) => None,
+            None => Some(QualityDetector::new()),
+        };
+
+        RecordChecker {
+            options: options.clone(),
+            detector,
+            duplicates: DuplicateTracker::new(options.duplicates),
+            summary: ValidationSummary::default(),
+            crlf_reported: false,
+        }
+    }
+
+    /// 检查一条记录，`line_num` 为标题行行号，返回该记录违反的所有规则（已计数）
+    pub(crate) fn check(&mut self, record: &FastqRecordRef<'_>, line_num: usize) -> Vec<Violation> {
+        self.summary.records += 1;
+        self.summary.observe_read(record.sequence.len());
+
+        if let Some(ref mut d) = self.detector {
+            d.observe(record.quality);
+        }
+
+        // 自动识别平台时以第一条记录为准，之后格式不同的标题行会被报告
+        if self.options.platform == Some(Platform::Auto) && record.header.starts_with(b"@") {
+            self.options.platform = Platform::detect(record.header);
+        }
+
+        // 验证记录；没有错误（可以有警告）时再检查读段名是否重复
+        let mut violations = validate_record_all(record, line_num, &self.options);
+        let first_line = if record.header.starts_with(b"@") && self.options.duplicates != DuplicateCheck::Off {
+            self.duplicates.observe(&duplicate_key(record, self.options.interleaved), line_num)
+        } else {
+            None
+        };
+        if !violations.iter().any(|v| v.severity == Severity::Error)
+            && let Some(first_line) = first_line
+        {
+            let err = duplicate_error(record.read_id(), first_line, line_num);
+            violations.push(self.violation(err));
+        }
+
+        violations.retain(|v| self.count(v));
+        violations
+    }
+
+    /// 读取器第一次遇到 Windows 换行符时返回一个 `CrlfLineEnding`，每个输入只报告一次
+    ///
+    /// `crlf_line` 为 `FastqReader::crlf_line`，返回的问题已经计数。
+    pub(crate) fn check_line_ending(&mut self, crlf_line: Option<usize>) -> Option<Violation> {
+        let line = crlf_line.filter(|_| !self.crlf_reported)?;
+        self.crlf_reported = true;
+        let violation = self.violation(FastqError::CrlfLineEnding(line));
+        self.count(&violation).then_some(violation)
+    }
+
+    /// 统计一个问题，包括其他检查（如交错格式的配对）发现的问题
+    ///
+    /// 严重程度按配置决定，见 `count`。
+    pub(crate) fn count_error(&mut self, err: &FastqError) -> bool {
+        let severity = self.options.severity_of(err.kind());
+        self.summary.count(err, severity);
+        severity != Severity::Off
+    }
+
+    /// 按问题自身的严重程度计为错误或警告；`Severity::Off` 时不计数并返回 `false`，调用方也不应再报告它
+    fn count(&mut self, violation: &Violation) -> bool {
+        self.summary.count(&violation.error, violation.severity);
+        violation.severity != Severity::Off
+    }
+
+    fn violation(&self, err: FastqError) -> Violation {
+        let severity = self.options.severity_of(err.kind());
+        Violation::new(err, severity)
+    }
+
+    /// 已发现的错误数（不含警告）
+    pub(crate) fn errors(&self) -> usize {
+        self.summary.errors
+    }
+
+    /// 加上其他来源的 `other_errors` 个错误后是否达到 `max_errors`
+    pub(crate) fn error_limit_reached(&self, other_errors: usize) -> bool {
+        self.options.error_limit_reached(self.summary.errors + other_errors)
+    }
+
+    /// 统计读取器报告的记录级错误
+    ///
+    /// 文件在记录中途结束计为一条错误记录；错位恢复跳过的行只计为一个错误。
+    pub(crate) fn count_reader_error(&mut self, err: &FastqError) -> bool {
+        if matches!(err, FastqError::TruncatedRecord { .. }) {
+            self.summary.records += 1;
+        }
+        self.count_error(err)
+    }
+
+    /// 结束检查，返回统计结果与最终使用的选项（含识别出的平台）
+    pub(crate) fn finish(self) -> (ValidationSummary, CheckOptions, DuplicateTracker) {
+        let detected = self.detector.and_then(|d| d.guess());
+        let summary = ValidationSummary {
+            quality_encoding: self.options.quality_encoding.or(detected),
+            platform: self.options.platform.filter(|&p| p != Platform::Auto),
+            ..self.summary
+        };
+        (summary, self.options, self.duplicates)
+    }
+}
+
+/// 读取下一条记录，期间遇到的记录级错误（截断、跳过的行）会被统计并写入错误输出
+///
+/// 返回 `false` 表示输入结束。
+pub(crate) fn advance<B: BufRead, W: Write>(
+    reader: &mut FastqReader<B>,
+    checker: &mut RecordChecker,
+    error_file: &mut Option<ErrorReporter<W>>,
+    label: Option<&str>,
+) -> Result<bool, FastqError> {
+    loop {
+        match reader.read_next() {
+            Ok(more) => return Ok(more),
+            Err(err @ (FastqError::TruncatedRecord { .. } | FastqError::SkippedLines { .. })) => {
+                if checker.count_reader_error(&err)
+                    && let Some(file) = error_file
+                {
+                    file.write_error(label, &err, Some(reader.byte_offset()), reader.raw_lines())?;
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// 第一遍：逐条读取和验证记录
+fn check_records<R: Read + Send, W: Write>(
+    input: R,
+    error_file: &mut Option<ErrorReporter<W>>,
+    options: &CheckOptions,
+) -> Result<(ValidationSummary, CheckOptions, DuplicateTracker), FastqError> {
+    let mut reader = FastqReader::from_read(input)?.with_resync(options.resync);
+    let mut checker = RecordChecker::new(options);
+    let mut mates = options.interleaved.then(MateTracker::new);
+    let mut index_lengths = options
+        .check_index_length
+        .then(|| IndexLengthTracker::new(options.detect_records));
+
+    // 逐条读取和验证 FASTQ 记录
+    let mut stopped_early = false;
+    while advance(&mut reader, &mut checker, error_file, None)? {
+        let record = reader.record();
+
+        // 如果提供了错误输出文件，则写入错误记录及其所有错误和警告
+        let mut violations = checker.check(&record, reader.line_num());
+        violations.extend(checker.check_line_ending(reader.crlf_line()));
+        if !violations.is_empty()
+            && let Some(file) = error_file
+        {
+            file.write_errors(None, &violations, Some(reader.byte_offset()), record.lines())?;
+        }
+
+        let (line_num, offset) = (reader.line_num(), reader.byte_offset());
+        if let Some(ref mut mates) = mates {
+            mates.observe(&record, line_num, offset, &mut checker, error_file)?;
+        }
+        if let Some(ref mut index_lengths) = index_lengths {
+            index_lengths.observe(&record, line_num, offset, &mut checker, error_file)?;
+        }
+
+        if checker.error_limit_reached(0) {
+            stopped_early = true;
+            break;
+        }
+    }
+
+    // 提前停止时不再报告等待中的读段：它们的另一端或多数 index 长度还没有读到
+    if !stopped_early {
+        if let Some(ref mut index_lengths) = index_lengths {
+            index_lengths.finish(&mut checker, error_file)?;
+        }
+        if let Some(ref mut mates) = mates {
+            mates.finish(&mut checker, error_file)?;
+        }
+    }
+
+    let (mut summary, options, duplicates) = checker.finish();
+    summary.bytes_read = reader.bytes_read();
+    summary.stopped_early = stopped_early;
+    Ok((summary, options, duplicates))
+}
+
+/// 第二遍：确认 Bloom 过滤器筛出的候选重复读段名，确认的重复计入 `summary`
+///
+/// 错误总数达到 `max_errors` 时停止并设置 `summary.stopped_early`。
+fn confirm_duplicates<R: Read + Send, W: Write>(
+    input: R,
+    error_file: &mut Option<ErrorReporter<W>>,
+    options: &CheckOptions,
+    mut confirmer: DuplicateConfirmer,
+    summary: &mut ValidationSummary,
+) -> Result<(), FastqError> {
+    let mut reader = FastqReader::from_read(input)?.with_resync(options.resync);
+    let severity = options.severity_of("duplicate_read_id");
+
+    loop {
+        match reader.read_next() {
+            Ok(true) => {}
+            Ok(false) => break,
+            // 这些错误在第一遍中已经报告过
+            Err(FastqError::TruncatedRecord { .. } | FastqError::SkippedLines { .. }) => continue,
+            Err(err) => return Err(err),
+        }
+
+        let record = reader.record();
+        let line_num = reader.line_num();
+        if !record.header.starts_with(b"@") {
+            continue;
+        }
+
+        // 与第一遍一致：格式有误的记录已按其他错误报告
+        let first_line = confirmer.observe(&duplicate_key(&record, options.interleaved), line_num);
+        if let Some(first_line) = first_line
+            && validate_record_with(&record, line_num, options).is_ok()
+        {
+            let err = duplicate_error(record.read_id(), first_line, line_num);
+            summary.count(&err, severity);
+            if let Some(file) = error_file {
+                file.write_error(None, &err, Some(reader.byte_offset()), record.lines())?;
+            }
+            // 与第一遍共用 max_errors 的上限
+            if options.error_limit_reached(summary.errors) {
+                summary.stopped_early = true;
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 重复检测比较的读段名
+///
+/// 交错格式中同一片段的两端读段名相同（如 Casava 1.8+ 只在注释中区分 1、2），因此按去掉配对后缀的名称
+/// 加上读段序号比较；没有读段序号的两端仍会被视为重复。
+fn duplicate_key<'a>(record: &FastqRecordRef<'a>, interleaved: bool) -> Cow<'a, [u8]> {
+    if !interleaved {
+        return Cow::Borrowed(record.read_id());
+    }
+    // 读段名中不会有空白，用空格分隔名称与序号
+    let mut key = mate_name(record.read_id()).to_vec();
+    key.push(b' ');
+    key.push(read_number(record.header).map_or(b'0', |n| b'0' + n));
+    Cow::Owned(key)
+}
+
+pub(crate) fn duplicate_error(id: &[u8], first_line: usize, line: usize) -> FastqError {
+    FastqError::DuplicateReadId {
+        name: String::from_utf8_lossy(id).into_owned(),
+        first_line,
+        line,
+    }
+}
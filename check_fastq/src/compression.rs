@@ -0,0 +1,81 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use bzip2::bufread::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+use xz2::bufread::XzDecoder;
+
+use crate::lang;
+
+/// 输入文件的压缩格式，根据文件开头的魔数判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bgzf,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// 根据输入开头的字节判断压缩格式
+    pub fn detect(magic: &[u8]) -> Compression {
+        if magic.starts_with(&[0x1f, 0x8b]) {
+            // BGZF 是带有 "BC" 额外字段的 gzip
+            if magic.len() >= 14 && magic[3] & 0x04 != 0 && &magic[12..14] == b"BC" {
+                Compression::Bgzf
+            } else {
+                Compression::Gzip
+            }
+        } else if magic.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Compression::None => lang().pick("无压缩", "none"),
+            Compression::Gzip => "gzip",
+            Compression::Bgzf => "bgzip",
+            Compression::Bzip2 => "bzip2",
+            Compression::Xz => "xz",
+            Compression::Zstd => "zstd",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 探测输入的压缩格式，返回透明解压后的读取器
+///
+/// gzip 按多成员方式解压，因此按 lane 直接拼接的 `.fastq.gz` 与 BGZF 都能完整读取。
+pub fn decompress<'a, R>(mut reader: R) -> io::Result<(Box<dyn BufRead + Send + 'a>, Compression)>
+where
+    R: BufRead + Send + 'a,
+{
+    let compression = Compression::detect(reader.fill_buf()?);
+    let decoded: Box<dyn BufRead + Send + 'a> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip | Compression::Bgzf => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(reader))),
+        Compression::Xz => Box::new(BufReader::new(XzDecoder::new_multi_decoder(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+    };
+    Ok((decoded, compression))
+}
+
+/// 打开文件并按需解压
+pub fn open_input<P: AsRef<Path>>(path: P) -> io::Result<(Box<dyn BufRead + Send>, Compression)> {
+    let file = File::open(path.as_ref())?;
+    decompress(BufReader::new(file))
+}
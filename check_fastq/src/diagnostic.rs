@@ -0,0 +1,357 @@
+use std::fmt;
+
+use crate::i18n::{error_message, field_name, lang, tr};
+use crate::{header_read_id, FastqError, Severity};
+
+/// 显示的源码行超过该宽度时只显示标注附近的部分
+const MAX_WIDTH: usize = 100;
+/// 跳过的行较多时最多显示的行数
+const MAX_LINES: usize = 8;
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// rustc 风格的错误诊断：错误代码和信息、文件位置，以及出错的行和指向出错位置的 `^`
+///
+/// ```text
+/// 错误[FQ006]: 序列 (行 2, 第 5 列) 含有非法字符 'X'
+///  --> reads.fastq:2:5
+///   |
+/// 2 | ACGTXACGT
+///   |     ^ 非法碱基
+/// ```
+pub struct Diagnostic<'a> {
+    error: &'a FastqError,
+    lines: Vec<&'a [u8]>,
+    source: Option<&'a str>,
+    color: bool,
+    severity: Severity,
+}
+
+/// 要显示的一行：行号、内容与可选的标注
+struct Snippet<'a> {
+    line: usize,
+    text: &'a [u8],
+    mark: Option<Mark>,
+}
+
+/// 行内的标注：起始位置（从 0 开始）、宽度与说明
+struct Mark {
+    start: usize,
+    len: usize,
+    label: String,
+}
+
+impl<'a> Diagnostic<'a> {
+    /// `lines` 为出错记录的原始行（与错误输出中写出的行相同）
+    pub fn new(error: &'a FastqError, lines: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        Diagnostic {
+            error,
+            lines: lines.into_iter().collect(),
+            source: None,
+            color: false,
+            severity: Severity::Error,
+        }
+    }
+
+    /// 位置信息中显示的输入名称，如文件路径
+    pub fn source(mut self, source: &'a str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// 是否使用 ANSI 颜色
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// 问题的严重程度，决定标题是“错误”还是“警告”，默认为 `Severity::Error`
+    pub fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// 标题的文字与颜色
+    fn title(&self) -> (&'static str, &'static str) {
+        match self.severity {
+            Severity::Error | Severity::Off => (lang().pick("错误", "error"), RED),
+            Severity::Warning => (lang().pick("警告", "warning"), YELLOW),
+            Severity::Info => (lang().pick("提示", "info"), BLUE),
+        }
+    }
+
+    fn paint(&self, f: &mut fmt::Formatter<'_>, style: &str, text: &str) -> fmt::Result {
+        if self.color {
+            write!(f, "{}{}{}", style, text, RESET)
+        } else {
+            f.write_str(text)
+        }
+    }
+
+    /// 第 `i` 行（记录内从 0 开始），不存在时为空
+    fn line(&self, i: usize) -> &'a [u8] {
+        self.lines.get(i).copied().unwrap_or_default()
+    }
+
+    /// 错误行号对应记录中的第几行
+    fn line_index(&self) -> usize {
+        match self.error {
+            FastqError::InvalidBase { .. }
+            | FastqError::LowercaseBase { .. }
+            | FastqError::ReadTooShort { .. }
+            | FastqError::ReadTooLong { .. } => 1,
+            FastqError::InvalidPlusLine(_)
+            | FastqError::PlusLineMismatch { .. }
+            | FastqError::PlusLineNotBare(_) => 2,
+            FastqError::LengthMismatch { .. } | FastqError::InvalidQuality { .. } => 3,
+            _ => 0,
+        }
+    }
+
+    /// 记录中第 `i` 行的显示内容
+    fn snippet(&self, i: usize, mark: Option<Mark>) -> Snippet<'a> {
+        let first = self.error.line().unwrap_or(1).saturating_sub(self.line_index());
+        Snippet {
+            line: first + i,
+            text: self.line(i),
+            mark,
+        }
+    }
+
+    /// 标注标题行中的读段名
+    fn mark_read_id(&self, label: String) -> Vec<Snippet<'a>> {
+        let id = header_read_id(self.line(0));
+        let mark = Mark {
+            start: 1,
+            len: id.len(),
+            label,
+        };
+        vec![self.snippet(0, Some(mark))]
+    }
+
+    /// 标注标题行中的字段 `needle`，找不到时标注行尾
+    ///
+    /// 优先匹配前后都是分隔符（`:`、`/`、`=`、空白）的完整字段，避免标到其他字段中间的相同字符。
+    fn mark_in_header(&self, needle: &[u8], label: String) -> Vec<Snippet<'a>> {
+        let header = self.line(0);
+        let is_delimiter = |b: Option<&u8>| {
+            b.is_none_or(|&b| matches!(b, b'@' | b':' | b'/' | b'=') || b.is_ascii_whitespace())
+        };
+        let mut matches = (0..header.len().saturating_sub(needle.len()) + 1)
+            .filter(|&pos| !needle.is_empty() && header[pos..].starts_with(needle));
+        let found = matches
+            .clone()
+            .find(|&pos| {
+                let before = pos.checked_sub(1).and_then(|p| header.get(p));
+                is_delimiter(before) && is_delimiter(header.get(pos + needle.len()))
+            })
+            .or_else(|| matches.next());
+        let mark = match found {
+            Some(pos) => Mark {
+                start: pos,
+                len: needle.len(),
+                label,
+            },
+            None => Mark {
+                start: header.len(),
+                len: 1,
+                label,
+            },
+        };
+        vec![self.snippet(0, Some(mark))]
+    }
+
+    /// 需要显示的行及其标注
+    fn snippets(&self) -> Vec<Snippet<'a>> {
+        let mark = |start, len, label| Some(Mark { start, len, label });
+
+        match self.error {
+            FastqError::InvalidHeader(_) => {
+                vec![self.snippet(0, mark(0, 1, tr!("应以 @ 开头", "expected '@'")))]
+            }
+            FastqError::InvalidPlusLine(_) => {
+                vec![self.snippet(2, mark(0, 1, tr!("应以 + 开头", "expected '+'")))]
+            }
+            FastqError::PlusLineMismatch { .. } => {
+                let (header, plus) = (self.line(0), self.line(2));
+                // 跳过开头的 @ 与 +，从标识符第一个不同的字符开始标注
+                let ids = header.iter().skip(1).zip(plus.iter().skip(1));
+                let same = 1 + ids.take_while(|(a, b)| a == b).count();
+                let len = plus.len().saturating_sub(same).max(1);
+                vec![
+                    self.snippet(0, None),
+                    self.snippet(2, mark(same, len, tr!("与标题行不一致", "differs from the header"))),
+                ]
+            }
+            FastqError::PlusLineNotBare(_) => {
+                let len = self.line(2).len().saturating_sub(1).max(1);
+                vec![self.snippet(2, mark(1, len, tr!("加号行应只有 +", "expected a bare '+'")))]
+            }
+            FastqError::LengthMismatch { seq_len, qual_len, .. } => {
+                if seq_len > qual_len {
+                    let label = tr!("质量值在此之前结束", "quality ends before this column");
+                    vec![
+                        self.snippet(1, mark(*qual_len, seq_len - qual_len, label)),
+                        self.snippet(3, None),
+                    ]
+                } else {
+                    let label = tr!("序列在此之前结束", "sequence ends before this column");
+                    vec![
+                        self.snippet(1, None),
+                        self.snippet(3, mark(*seq_len, qual_len - seq_len, label)),
+                    ]
+                }
+            }
+            FastqError::InvalidBase { column, .. } => {
+                vec![self.snippet(1, mark(column - 1, 1, tr!("非法碱基", "invalid base")))]
+            }
+            FastqError::LowercaseBase { column, .. } => {
+                vec![self.snippet(1, mark(column - 1, 1, tr!("小写碱基", "lowercase base")))]
+            }
+            FastqError::ReadTooShort { length, min, .. } => {
+                let label = tr!("至少应有 {} 个碱基", "expected at least {} bases", min);
+                vec![self.snippet(1, mark(*length, 1, label))]
+            }
+            FastqError::ReadTooLong { length, max, .. } => {
+                let label = tr!("超过 {} 个碱基", "beyond {} bases", max);
+                vec![self.snippet(1, mark(*max, length - max, label))]
+            }
+            FastqError::InvalidQuality { column, encoding, .. } => {
+                let label = tr!("超出 {} 的范围", "outside the {} range", encoding);
+                vec![self.snippet(3, mark(column - 1, 1, label))]
+            }
+            FastqError::DuplicateReadId { first_line, .. } => {
+                self.mark_read_id(tr!("首次出现在行 {}", "first seen at line {}", first_line))
+            }
+            FastqError::OrphanRead { .. } => self.mark_read_id(tr!("没有配对的另一端", "no mate")),
+            FastqError::MateOrder { expected, .. } => {
+                self.mark_read_id(tr!("读段序号应为 {}", "expected read number {}", expected))
+            }
+            FastqError::MalformedHeader { field, value, .. } => {
+                let label = if value.is_empty() {
+                    tr!("缺少{}", "missing {}", field_name(field))
+                } else {
+                    tr!("无效的{}", "invalid {}", field_name(field))
+                };
+                self.mark_in_header(value.as_bytes(), label)
+            }
+            FastqError::FilteredRead(_) => {
+                let header = self.line(0);
+                let start = header.windows(3).position(|w| w == b":Y:").map_or(0, |p| p + 1);
+                vec![self.snippet(0, mark(start, 1, tr!("过滤标记", "filter flag")))]
+            }
+            FastqError::IndexLengthMismatch { expected, .. } => {
+                let header = self.line(0);
+                let start = header.iter().rposition(|&b| b == b':').map_or(0, |p| p + 1);
+                let label = tr!("应为 {} 个碱基", "expected {} bases", expected);
+                vec![self.snippet(0, mark(start, header.len() - start, label))]
+            }
+            FastqError::PairNameMismatch { r1_line, r2_line, .. } => {
+                let r2_header = self.line(4);
+                let len = header_read_id(r2_header).len();
+                vec![
+                    Snippet {
+                        line: *r1_line,
+                        text: self.line(0),
+                        mark: None,
+                    },
+                    Snippet {
+                        line: *r2_line,
+                        text: r2_header,
+                        mark: mark(1, len, tr!("与 R1 不一致", "differs from R1")),
+                    },
+                ]
+            }
+            FastqError::TruncatedRecord { .. } | FastqError::SkippedLines { .. } => {
+                (0..self.lines.len().min(MAX_LINES)).map(|i| self.snippet(i, None)).collect()
+            }
+            // 自定义规则的行号不一定是标题行，换行符在显示的行中已经去掉，只显示位置
+            FastqError::PairCountMismatch { .. }
+            | FastqError::RuleViolation { .. }
+            | FastqError::CrlfLineEnding(_)
+            | FastqError::Io(_)
+            | FastqError::Format(_) => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (title, style) = self.title();
+        self.paint(f, style, &format!("{}[{}]", title, self.error.code()))?;
+        self.paint(f, BOLD, &format!(": {}", error_message(self.error)))?;
+        writeln!(f)?;
+
+        let snippets = self.snippets();
+        let Some(line) = self.error.line() else {
+            return Ok(());
+        };
+
+        // 位置：有标注时指向标注的列
+        let column = snippets
+            .iter()
+            .find_map(|s| s.mark.as_ref().map(|m| (s.line, m.start + 1)));
+        let source = self.source.unwrap_or(lang().pick("<输入>", "<input>"));
+        let width = snippets.iter().map(|s| s.line).max().unwrap_or(line).to_string().len();
+        let gutter = " ".repeat(width);
+
+        write!(f, "{}", gutter)?;
+        self.paint(f, BLUE, "--> ")?;
+        match column {
+            Some((line, column)) => writeln!(f, "{}:{}:{}", source, line, column)?,
+            None => writeln!(f, "{}:{}", source, line)?,
+        }
+        if snippets.is_empty() {
+            return Ok(());
+        }
+
+        self.paint(f, BLUE, &format!("{} |", gutter))?;
+        writeln!(f)?;
+        for snippet in &snippets {
+            let (text, pad) = clip(snippet.text, snippet.mark.as_ref());
+            self.paint(f, BLUE, &format!("{:>width$} | ", snippet.line))?;
+            writeln!(f, "{}", text)?;
+
+            if let Some(mark) = &snippet.mark {
+                self.paint(f, BLUE, &format!("{} | ", gutter))?;
+                let carets = "^".repeat(mark.len.clamp(1, MAX_WIDTH));
+                write!(f, "{}", " ".repeat(pad))?;
+                self.paint(f, style, &format!("{} {}", carets, mark.label))?;
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 截取过长的行中标注附近的部分，返回显示文本和标注前的空格数
+///
+/// 不可打印的字节显示为 `?`，保证 `^` 与出错的字节对齐。
+fn clip(text: &[u8], mark: Option<&Mark>) -> (String, usize) {
+    let start = match mark {
+        Some(mark) if text.len() > MAX_WIDTH => mark.start.saturating_sub(MAX_WIDTH / 2),
+        _ => 0,
+    };
+    let end = text.len().min(start + MAX_WIDTH);
+
+    let mut shown = String::new();
+    if start > 0 {
+        shown.push_str("...");
+    }
+    let pad = mark.map_or(0, |m| m.start - start + shown.len());
+    shown.extend(text[start..end].iter().map(|&b| {
+        if b == b' ' || b.is_ascii_graphic() {
+            b as char
+        } else {
+            '?'
+        }
+    }));
+    if end < text.len() {
+        shown.push_str("...");
+    }
+    (shown, pad)
+}
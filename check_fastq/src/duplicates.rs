@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::str::FromStr;
+
+use crate::i18n::tr;
+
+/// 重复读段名的检测方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateCheck {
+    /// 不检测
+    #[default]
+    Off,
+    /// 用哈希表精确记录所有读段名，内存随读段数增长，适合较小的文件
+    Exact,
+    /// 用固定大小（字节）的 Bloom 过滤器筛出可能重复的读段名，再读一遍输入确认
+    Bloom { memory: usize },
+}
+
+impl DuplicateCheck {
+    /// Bloom 模式的默认内存上限（512 MiB）
+    pub const DEFAULT_BLOOM_MEMORY: usize = 512 * 1024 * 1024;
+}
+
+impl FromStr for DuplicateCheck {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(DuplicateCheck::Off),
+            "exact" => Ok(DuplicateCheck::Exact),
+            "bloom" => Ok(DuplicateCheck::Bloom {
+                memory: DuplicateCheck::DEFAULT_BLOOM_MEMORY,
+            }),
+            _ => Err(tr!(
+                "未知的重复检测方式: {}（可选 off、exact、bloom）",
+                "unknown duplicate check: {} (expected off, exact, bloom)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DuplicateCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateCheck::Off => f.write_str("off"),
+            DuplicateCheck::Exact => f.write_str("exact"),
+            DuplicateCheck::Bloom { .. } => f.write_str("bloom"),
+        }
+    }
+}
+
+/// 每个读段名在 Bloom 过滤器中置位的个数
+const BLOOM_HASHES: u64 = 7;
+
+/// 简单的 Bloom 过滤器
+pub(crate) struct BloomFilter {
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    fn with_memory(bytes: usize) -> Self {
+        BloomFilter {
+            bits: vec![0; (bytes / 8).max(1)],
+        }
+    }
+
+    /// 插入读段名，返回插入前是否可能已经存在
+    fn insert(&mut self, id: &[u8]) -> bool {
+        let nbits = self.bits.len() as u64 * 64;
+        let (h1, h2) = hash_pair(id);
+
+        let mut present = true;
+        for i in 0..BLOOM_HASHES {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % nbits;
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                present = false;
+                self.bits[word] |= mask;
+            }
+        }
+        present
+    }
+}
+
+/// 计算两个独立的哈希值，用于双重哈希
+fn hash_pair(id: &[u8]) -> (u64, u64) {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    let h1 = hasher.finish();
+
+    let mut hasher = DefaultHasher::new();
+    0x9e37_79b9_7f4a_7c15u64.hash(&mut hasher);
+    id.hash(&mut hasher);
+    // 第二个哈希必须是奇数，保证各次探测位置不同
+    (h1, hasher.finish() | 1)
+}
+
+/// 检查过程中跟踪已出现的读段名
+pub(crate) enum DuplicateTracker {
+    Off,
+    /// 读段名 -> 首次出现的标题行行号
+    Exact(HashMap<Box<[u8]>, usize>),
+    /// Bloom 过滤器与第一遍中可能重复的读段名
+    Bloom(BloomFilter, HashMap<Box<[u8]>, usize>),
+}
+
+impl DuplicateTracker {
+    pub(crate) fn new(check: DuplicateCheck) -> Self {
+        match check {
+            DuplicateCheck::Off => DuplicateTracker::Off,
+            DuplicateCheck::Exact => DuplicateTracker::Exact(HashMap::new()),
+            DuplicateCheck::Bloom { memory } => {
+                DuplicateTracker::Bloom(BloomFilter::with_memory(memory), HashMap::new())
+            }
+        }
+    }
+
+    /// 记录一个读段名；精确模式下若重复则返回首次出现的行号
+    ///
+    /// Bloom 模式只收集候选名单，确认需要在第二遍中通过 `DuplicateConfirmer` 完成。
+    pub(crate) fn observe(&mut self, id: &[u8], line: usize) -> Option<usize> {
+        match self {
+            DuplicateTracker::Off => None,
+            DuplicateTracker::Exact(seen) => match seen.get(id) {
+                Some(&first) => Some(first),
+                None => {
+                    seen.insert(id.into(), line);
+                    None
+                }
+            },
+            DuplicateTracker::Bloom(filter, candidates) => {
+                if filter.insert(id) && !candidates.contains_key(id) {
+                    candidates.insert(id.into(), 0);
+                }
+                None
+            }
+        }
+    }
+
+    /// Bloom 模式下需要第二遍确认的候选名单，其他模式返回 `None`
+    pub(crate) fn into_confirmer(self) -> Option<DuplicateConfirmer> {
+        match self {
+            DuplicateTracker::Bloom(_, candidates) if !candidates.is_empty() => {
+                Some(DuplicateConfirmer { candidates })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Bloom 模式的第二遍：只跟踪候选读段名，排除假阳性并找回首次出现的行号
+pub(crate) struct DuplicateConfirmer {
+    /// 候选读段名 -> 首次出现的行号（尚未遇到时为 0）
+    candidates: HashMap<Box<[u8]>, usize>,
+}
+
+impl DuplicateConfirmer {
+    /// 记录一个读段名，若确认重复则返回首次出现的行号
+    pub(crate) fn observe(&mut self, id: &[u8], line: usize) -> Option<usize> {
+        let first = self.candidates.get_mut(id)?;
+        if *first == 0 {
+            *first = line;
+            None
+        } else {
+            Some(*first)
+        }
+    }
+}
@@ -0,0 +1,583 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::check::RecordChecker;
+use crate::i18n::tr;
+use crate::report::ErrorReporter;
+use crate::{FastqError, FastqRecordRef};
+
+/// 标题行格式所属的测序平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// 根据标题行自动识别
+    Auto,
+    /// Illumina Casava 1.8+
+    Illumina,
+    /// Oxford Nanopore（MinKNOW / Guppy / Dorado 输出的 `key=value` 注释）
+    Ont,
+    /// PacBio（`movie/zmw/start_end` 或 `movie/zmw/ccs` 形式的读段名）
+    PacBio,
+}
+
+impl Platform {
+    /// 根据标题行的大致形式识别测序平台，无法识别时返回 `None`
+    ///
+    /// 只看标题行的整体形状，不验证各字段，因此格式有误的标题行也能被识别出来并报告具体的字段。
+    pub fn detect(header: &[u8]) -> Option<Platform> {
+        let header = header.strip_prefix(b"@").unwrap_or(header);
+        let (id, comment) = match header.iter().position(|b| b.is_ascii_whitespace()) {
+            Some(pos) => (&header[..pos], &header[pos..]),
+            None => (header, &b""[..]),
+        };
+
+        if comment
+            .split(|b| b.is_ascii_whitespace())
+            .any(|token| token.starts_with(b"runid="))
+        {
+            return Some(Platform::Ont);
+        }
+
+        let mut parts = id.split(|&b| b == b'/');
+        if let (Some([b'm', ..]), Some(zmw), Some(_)) = (parts.next(), parts.next(), parts.next())
+            && !zmw.is_empty()
+            && zmw.iter().all(u8::is_ascii_digit)
+        {
+            return Some(Platform::PacBio);
+        }
+
+        // 7 个字段，bcl2fastq 可能在最后再加一个 UMI 字段
+        if matches!(id.iter().filter(|&&b| b == b':').count(), 6 | 7) {
+            return Some(Platform::Illumina);
+        }
+
+        None
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Platform::Auto),
+            "illumina" => Ok(Platform::Illumina),
+            "ont" | "nanopore" | "oxford nanopore" => Ok(Platform::Ont),
+            "pacbio" => Ok(Platform::PacBio),
+            _ => Err(tr!(
+                "未知的测序平台: {}（可选 auto、illumina、ont、pacbio）",
+                "unknown platform: {} (expected auto, illumina, ont, pacbio)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Platform::Auto => "auto",
+            Platform::Illumina => "Illumina",
+            Platform::Ont => "Oxford Nanopore",
+            Platform::PacBio => "PacBio",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 标题行中无法解析或取值无效的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderFieldError {
+    /// 字段标识（与语言无关），如 `lane`、`read_name`
+    pub field: &'static str,
+    /// 字段的原始内容，缺少该字段时为空
+    pub value: String,
+}
+
+impl HeaderFieldError {
+    fn new(field: &'static str, value: &str) -> Self {
+        HeaderFieldError {
+            field,
+            value: value.to_string(),
+        }
+    }
+
+    fn missing(field: &'static str) -> Self {
+        HeaderFieldError::new(field, "")
+    }
+}
+
+/// 按平台解析后的标题行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlatformHeader<'a> {
+    Illumina(IlluminaHeader<'a>),
+    Ont(OntHeader<'a>),
+    PacBio(PacBioHeader<'a>),
+}
+
+impl<'a> PlatformHeader<'a> {
+    /// 按指定平台解析标题行；`Platform::Auto` 先调用 `Platform::detect` 识别平台
+    pub fn parse(header: &'a [u8], platform: Platform) -> Result<Self, HeaderFieldError> {
+        let platform = match platform {
+            Platform::Auto => Platform::detect(header).ok_or_else(|| {
+                HeaderFieldError::new("platform", &String::from_utf8_lossy(header))
+            })?,
+            other => other,
+        };
+
+        match platform {
+            Platform::Illumina => IlluminaHeader::parse(header).map(PlatformHeader::Illumina),
+            Platform::Ont => OntHeader::parse(header).map(PlatformHeader::Ont),
+            Platform::PacBio => PacBioHeader::parse(header).map(PlatformHeader::PacBio),
+            Platform::Auto => unreachable!(),
+        }
+    }
+
+    /// 标题行所属的测序平台
+    pub fn platform(&self) -> Platform {
+        match self {
+            PlatformHeader::Illumina(_) => Platform::Illumina,
+            PlatformHeader::Ont(_) => Platform::Ont,
+            PlatformHeader::PacBio(_) => Platform::PacBio,
+        }
+    }
+}
+
+/// Illumina Casava 1.8+ 格式的标题行
+///
+/// `@<仪器>:<运行编号>:<流动槽>:<lane>:<tile>:<x>:<y>[:<UMI>] <读段序号>:<过滤标记>:<控制编号>:<index>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlluminaHeader<'a> {
+    pub instrument: &'a str,
+    pub run: u32,
+    pub flowcell: &'a str,
+    pub lane: u32,
+    pub tile: u32,
+    pub x: u32,
+    pub y: u32,
+    /// bcl2fastq 写入读段名的 UMI 序列（双 UMI 以 `+` 连接），没有时为 `None`
+    pub umi: Option<&'a str>,
+    /// 读段序号（1、2，index 读段可能为 3 或 4）
+    pub read: u8,
+    /// 过滤标记为 `Y`，即未通过 Illumina 的质控过滤
+    pub filtered: bool,
+    pub control: u32,
+    /// index 序列（双 index 以 `+` 连接），旧版本中也可能是样本编号
+    pub index: &'a str,
+}
+
+impl<'a> IlluminaHeader<'a> {
+    /// 解析标题行（可以带或不带开头的 `@`）
+    pub fn parse(header: &'a [u8]) -> Result<Self, HeaderFieldError> {
+        let header = header.strip_prefix(b"@").unwrap_or(header);
+        let header = std::str::from_utf8(header)
+            .map_err(|_| HeaderFieldError::new("header", &String::from_utf8_lossy(header)))?;
+
+        let (id, comment) = header
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| HeaderFieldError::new("comment", ""))?;
+
+        let id_fields: Vec<&str> = id.split(':').collect();
+        let (instrument, run, flowcell, lane, tile, x, y, umi) = match id_fields[..] {
+            [instrument, run, flowcell, lane, tile, x, y] => (instrument, run, flowcell, lane, tile, x, y, None),
+            [instrument, run, flowcell, lane, tile, x, y, umi] => {
+                (instrument, run, flowcell, lane, tile, x, y, Some(umi))
+            }
+            _ => return Err(HeaderFieldError::new("read_name", id)),
+        };
+        let comment = comment.trim_start();
+        let comment_fields: Vec<&str> = comment.split(':').collect();
+        let [read, filtered, control, index] = comment_fields[..] else {
+            return Err(HeaderFieldError::new("comment", comment));
+        };
+
+        let is_name = |s: &str| {
+            !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+        };
+        if !is_name(instrument) {
+            return Err(HeaderFieldError::new("instrument", instrument));
+        }
+        if !is_name(flowcell) {
+            return Err(HeaderFieldError::new("flowcell", flowcell));
+        }
+
+        let lane = parse_number::<u32>("lane", lane)?;
+        if lane == 0 {
+            return Err(HeaderFieldError::new("lane", "0"));
+        }
+        let read = parse_number::<u8>("read_number", read)?;
+        if read == 0 {
+            return Err(HeaderFieldError::new("read_number", "0"));
+        }
+
+        let filtered = match filtered {
+            "Y" => true,
+            "N" => false,
+            other => return Err(HeaderFieldError::new("filter_flag", other)),
+        };
+
+        let is_index = |s: &str| {
+            s.bytes().all(|b| b.is_ascii_digit())
+                || s.split('+').all(|part| part.bytes().all(|b| b"ACGTN".contains(&b)))
+        };
+        if !is_index(index) {
+            return Err(HeaderFieldError::new("index", index));
+        }
+        if let Some(umi) = umi
+            && !umi.split('+').all(|part| !part.is_empty() && part.bytes().all(|b| b"ACGTN".contains(&b)))
+        {
+            return Err(HeaderFieldError::new("umi", umi));
+        }
+
+        Ok(IlluminaHeader {
+            instrument,
+            run: parse_number("run", run)?,
+            flowcell,
+            lane,
+            tile: parse_number("tile", tile)?,
+            x: parse_number("x", x)?,
+            y: parse_number("y", y)?,
+            umi,
+            read,
+            filtered,
+            control: parse_number("control", control)?,
+            index,
+        })
+    }
+
+    /// index 序列的长度（不含 `+`）；index 字段为空或为样本编号时返回 `None`
+    pub fn index_len(&self) -> Option<usize> {
+        if self.index.is_empty() || self.index.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some(self.index.bytes().filter(|&b| b != b'+').count())
+    }
+}
+
+/// Oxford Nanopore 格式的标题行
+///
+/// `@<读段 UUID> runid=<运行编号> read=<读段编号> ch=<通道> start_time=<开始时间> ...`，
+/// 注释中的其他 `key=value` 字段会被忽略。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OntHeader<'a> {
+    /// 读段 UUID
+    pub read_id: &'a str,
+    pub run_id: &'a str,
+    /// 通道内的读段编号
+    pub read: u64,
+    pub channel: u32,
+    /// ISO 8601 格式的开始时间
+    pub start_time: &'a str,
+    pub flow_cell_id: Option<&'a str>,
+    pub sample_id: Option<&'a str>,
+    pub barcode: Option<&'a str>,
+}
+
+impl<'a> OntHeader<'a> {
+    /// 解析标题行（可以带或不带开头的 `@`）
+    pub fn parse(header: &'a [u8]) -> Result<Self, HeaderFieldError> {
+        let header = header.strip_prefix(b"@").unwrap_or(header);
+        let header = std::str::from_utf8(header)
+            .map_err(|_| HeaderFieldError::new("header", &String::from_utf8_lossy(header)))?;
+
+        let mut tokens = header.split_ascii_whitespace();
+        let read_id = tokens.next().unwrap_or_default();
+        if !is_uuid(read_id) {
+            return Err(HeaderFieldError::new("uuid", read_id));
+        }
+
+        let (mut run_id, mut read, mut channel, mut start_time) = (None, None, None, None);
+        let (mut flow_cell_id, mut sample_id, mut barcode) = (None, None, None);
+        for (key, value) in tokens.filter_map(|token| token.split_once('=')) {
+            match key {
+                "runid" => run_id = Some(value),
+                "read" => read = Some(value),
+                "ch" => channel = Some(value),
+                "start_time" => start_time = Some(value),
+                "flow_cell_id" => flow_cell_id = Some(value),
+                "sampleid" | "sample_id" => sample_id = Some(value),
+                "barcode" => barcode = Some(value),
+                _ => {}
+            }
+        }
+
+        let run_id = run_id.ok_or_else(|| HeaderFieldError::missing("runid"))?;
+        if run_id.is_empty() || !run_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(HeaderFieldError::new("runid", run_id));
+        }
+        let read = parse_number("read", read.ok_or_else(|| HeaderFieldError::missing("read"))?)?;
+        let channel = parse_number::<u32>("ch", channel.ok_or_else(|| HeaderFieldError::missing("ch"))?)?;
+        if channel == 0 {
+            return Err(HeaderFieldError::new("ch", "0"));
+        }
+        let start_time = start_time.ok_or_else(|| HeaderFieldError::missing("start_time"))?;
+        if !is_timestamp(start_time) {
+            return Err(HeaderFieldError::new("start_time", start_time));
+        }
+
+        Ok(OntHeader {
+            read_id,
+            run_id,
+            read,
+            channel,
+            start_time,
+            flow_cell_id,
+            sample_id,
+            barcode,
+        })
+    }
+}
+
+/// PacBio 格式的标题行
+///
+/// 子读段为 `@<movie>/<zmw>/<start>_<end>`，CCS (HiFi) 读段为 `@<movie>/<zmw>/ccs`，
+/// 按链拆分的 CCS 读段为 `@<movie>/<zmw>/ccs/fwd` 或 `.../ccs/rev`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacBioHeader<'a> {
+    /// movie 名称，如 `m64011_190830_220126`
+    pub movie: &'a str,
+    pub zmw: u64,
+    /// 子读段在 ZMW 读段中的区间 (start, end)，CCS 读段为 `None`
+    pub range: Option<(u64, u64)>,
+}
+
+impl<'a> PacBioHeader<'a> {
+    /// 解析标题行（可以带或不带开头的 `@`）
+    pub fn parse(header: &'a [u8]) -> Result<Self, HeaderFieldError> {
+        let header = header.strip_prefix(b"@").unwrap_or(header);
+        let header = std::str::from_utf8(header)
+            .map_err(|_| HeaderFieldError::new("header", &String::from_utf8_lossy(header)))?;
+        let id = header.split_ascii_whitespace().next().unwrap_or_default();
+
+        let Some((movie, rest)) = id.split_once('/') else {
+            return Err(HeaderFieldError::new("read_name", id));
+        };
+        let Some((zmw, kind)) = rest.split_once('/') else {
+            return Err(HeaderFieldError::new("read_name", id));
+        };
+
+        let is_movie = movie.starts_with('m')
+            && movie.len() > 1
+            && movie.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+        if !is_movie {
+            return Err(HeaderFieldError::new("movie", movie));
+        }
+        let zmw = parse_number("zmw", zmw)?;
+
+        let range = match kind {
+            "ccs" | "ccs/fwd" | "ccs/rev" => None,
+            _ => {
+                let (start, end) = kind
+                    .split_once('_')
+                    .ok_or_else(|| HeaderFieldError::new("subread_range", kind))?;
+                let start = parse_number::<u64>("subread_range", start)?;
+                let end = parse_number::<u64>("subread_range", end)?;
+                if start >= end {
+                    return Err(HeaderFieldError::new("subread_range", kind));
+                }
+                Some((start, end))
+            }
+        };
+
+        Ok(PacBioHeader { movie, zmw, range })
+    }
+}
+
+/// 是否为 8-4-4-4-12 形式的 UUID
+fn is_uuid(s: &str) -> bool {
+    let groups: Vec<&str> = s.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// 是否以 ISO 8601 的 `YYYY-MM-DDTHH:MM:SS` 开头（之后可以有小数秒和时区）
+fn is_timestamp(s: &str) -> bool {
+    const PATTERN: &[u8] = b"dddd-dd-ddTdd:dd:dd";
+    s.len() >= PATTERN.len()
+        && s.bytes().zip(PATTERN).all(|(b, &p)| match p {
+            b'd' => b.is_ascii_digit(),
+            _ => b == p,
+        })
+}
+
+fn parse_number<T: FromStr>(field: &'static str, value: &str) -> Result<T, HeaderFieldError> {
+    value.parse().map_err(|_| HeaderFieldError::new(field, value))
+}
+
+/// 检查 index 序列长度是否与多数读段一致
+///
+/// 多数长度由开头 `window` 条带 index 的记录决定；这些记录会被暂存，确定多数长度后再补报其中的异常，
+/// 之后的记录则直接报告。
+pub(crate) struct IndexLengthTracker {
+    window: usize,
+    counts: HashMap<usize, usize>,
+    pending: Vec<(usize, u64, usize, Vec<u8>)>, // (行号, 字节偏移, index 长度, 原始 4 行)
+    expected: Option<usize>,
+}
+
+impl IndexLengthTracker {
+    pub(crate) fn new(window: usize) -> Self {
+        IndexLengthTracker {
+            window: window.max(1),
+            counts: HashMap::new(),
+            pending: Vec::new(),
+            expected: None,
+        }
+    }
+
+    /// 处理一条记录，发现的错误计入 `checker` 并写入 `error_file`
+    pub(crate) fn observe<W: Write>(
+        &mut self,
+        record: &FastqRecordRef<'_>,
+        line_num: usize,
+        offset: u64,
+        checker: &mut RecordChecker,
+        error_file: &mut Option<ErrorReporter<W>>,
+    ) -> io::Result<()> {
+        let Some(length) = IlluminaHeader::parse(record.header).ok().and_then(|h| h.index_len()) else {
+            return Ok(());
+        };
+
+        if let Some(expected) = self.expected {
+            if length == expected {
+                return Ok(());
+            }
+            let err = FastqError::IndexLengthMismatch { line: line_num, length, expected };
+            if checker.count_error(&err)
+                && let Some(file) = error_file
+            {
+                file.write_error(None, &err, Some(offset), record.lines())?;
+            }
+            return Ok(());
+        }
+
+        *self.counts.entry(length).or_default() += 1;
+        let mut raw = Vec::new();
+        record.write_to(&mut raw)?;
+        self.pending.push((line_num, offset, length, raw));
+
+        if self.pending.len() >= self.window {
+            self.finish(checker, error_file)?;
+        }
+        Ok(())
+    }
+
+    /// 确定多数长度并补报暂存记录中的异常
+    pub(crate) fn finish<W: Write>(
+        &mut self,
+        checker: &mut RecordChecker,
+        error_file: &mut Option<ErrorReporter<W>>,
+    ) -> io::Result<()> {
+        if self.expected.is_some() || self.pending.is_empty() {
+            return Ok(());
+        }
+
+        // 票数相同时取较短的长度，保证结果确定
+        let expected = self
+            .counts
+            .iter()
+            .max_by_key(|&(&length, &count)| (count, std::cmp::Reverse(length)))
+            .map(|(&length, _)| length)
+            .unwrap_or_default();
+        self.expected = Some(expected);
+
+        for (line, offset, length, raw) in self.pending.drain(..) {
+            if length == expected {
+                continue;
+            }
+            let err = FastqError::IndexLengthMismatch { line, length, expected };
+            if checker.count_error(&err)
+                && let Some(file) = error_file
+            {
+                file.write_error(None, &err, Some(offset), raw.split(|&b| b == b'\n').take(4))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_illumina_header() {
+        let text = b"@M00123:45:000000000-ABCDE:1:1101:15589:1333 1:N:0:ATCACG+GTTAAC";
+        let header = IlluminaHeader::parse(text).unwrap();
+        assert_eq!(header.instrument, "M00123");
+        assert_eq!(header.flowcell, "000000000-ABCDE");
+        assert_eq!((header.lane, header.tile, header.x, header.y), (1, 1101, 15589, 1333));
+        assert_eq!(header.umi, None);
+        assert_eq!(header.read, 1);
+        assert!(!header.filtered);
+        assert_eq!(header.index_len(), Some(12));
+    }
+
+    #[test]
+    fn parses_illumina_header_with_umi() {
+        let text = b"@M00123:45:000000000-ABCDE:1:1101:15589:1333:ACGTACGT 1:N:0:ATCACG";
+        let header = IlluminaHeader::parse(text).unwrap();
+        assert_eq!(header.umi, Some("ACGTACGT"));
+        assert_eq!(header.y, 1333);
+
+        let err = IlluminaHeader::parse(b"@M00123:45:FC:1:1101:15589:1333:ACGX 1:N:0:ATCACG").unwrap_err();
+        assert_eq!(err, HeaderFieldError::new("umi", "ACGX"));
+    }
+
+    #[test]
+    fn reports_bad_illumina_fields() {
+        let err = IlluminaHeader::parse(b"@M00123:45:FC:0:1101:15589:1333 1:N:0:ATCACG").unwrap_err();
+        assert_eq!(err.field, "lane");
+        let err = IlluminaHeader::parse(b"@M00123:45:FC:1:1101:15589:1333 1:X:0:ATCACG").unwrap_err();
+        assert_eq!(err, HeaderFieldError::new("filter_flag", "X"));
+        let err = IlluminaHeader::parse(b"@M00123:45:FC:1:1101 1:N:0:ATCACG").unwrap_err();
+        assert_eq!(err.field, "read_name");
+        let err = IlluminaHeader::parse(b"@M00123:45:FC:1:1101:15589:1333").unwrap_err();
+        assert_eq!(err.field, "comment");
+    }
+
+    #[test]
+    fn parses_ont_header() {
+        let text = concat!(
+            "@0a2b4c6d-1234-5678-9abc-def012345678 runid=5a7f0b2c read=12 ch=101 ",
+            "start_time=2023-04-01T12:34:56Z barcode=barcode01",
+        );
+        let header = OntHeader::parse(text.as_bytes()).unwrap();
+        assert_eq!(header.read_id, "0a2b4c6d-1234-5678-9abc-def012345678");
+        assert_eq!((header.read, header.channel), (12, 101));
+        assert_eq!(header.barcode, Some("barcode01"));
+        assert_eq!(header.flow_cell_id, None);
+
+        let err = OntHeader::parse(b"@0a2b4c6d-1234-5678-9abc-def012345678 runid=5a7f read=1 ch=1").unwrap_err();
+        assert_eq!(err, HeaderFieldError::missing("start_time"));
+        let err = OntHeader::parse(b"@not-a-uuid runid=5a7f read=1 ch=1 start_time=2023-04-01T12:34:56Z").unwrap_err();
+        assert_eq!(err.field, "uuid");
+    }
+
+    #[test]
+    fn parses_pacbio_header() {
+        let subread = PacBioHeader::parse(b"@m64011_190830_220126/101/0_1500").unwrap();
+        assert_eq!((subread.movie, subread.zmw, subread.range), ("m64011_190830_220126", 101, Some((0, 1500))));
+        let ccs = PacBioHeader::parse(b"@m64011_190830_220126/101/ccs/fwd").unwrap();
+        assert_eq!(ccs.range, None);
+
+        let err = PacBioHeader::parse(b"@m64011_190830_220126/101/1500_0").unwrap_err();
+        assert_eq!(err.field, "subread_range");
+        let err = PacBioHeader::parse(b"@x64011/101/ccs").unwrap_err();
+        assert_eq!(err.field, "movie");
+    }
+
+    #[test]
+    fn detects_platform() {
+        let detect = Platform::detect;
+        assert_eq!(detect(b"@M00123:45:FC:1:1101:15589:1333 1:N:0:ATCACG"), Some(Platform::Illumina));
+        assert_eq!(detect(b"@M00123:45:FC:1:1101:15589:1333:ACGTACGT 1:N:0:ATCACG"), Some(Platform::Illumina));
+        assert_eq!(detect(b"@0a2b4c6d-1234-5678-9abc-def012345678 runid=5a7f read=1"), Some(Platform::Ont));
+        assert_eq!(detect(b"@m64011_190830_220126/101/ccs"), Some(Platform::PacBio));
+        assert_eq!(detect(b"@SEQ_ID1"), None);
+    }
+}
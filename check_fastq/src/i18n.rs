@@ -0,0 +1,231 @@
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{display_byte, FastqError};
+
+/// 按当前语言格式化文本：`tr!("中文 {}", "English {}", arg)`
+///
+/// 语言由 `set_lang` 决定，命令行程序的输出也使用这个宏。
+#[macro_export]
+macro_rules! tr {
+    ($zh:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::lang() {
+            $crate::Lang::Zh => format!($zh $(, $arg)*),
+            $crate::Lang::En => format!($en $(, $arg)*),
+        }
+    };
+}
+pub(crate) use crate::tr;
+
+/// 输出信息使用的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    /// 中文
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+/// 当前语言，0 为中文，1 为英文
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// 设置错误信息和摘要使用的语言（全局生效）
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 当前使用的语言
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        0 => Lang::Zh,
+        _ => Lang::En,
+    }
+}
+
+impl Lang {
+    /// 根据 `LC_ALL`、`LC_MESSAGES`、`LANG` 环境变量选择语言
+    ///
+    /// 取第一个非空的变量：以 `zh` 开头时为中文，`C`、`POSIX` 或未设置时保持默认的中文，其他语言环境为英文。
+    pub fn from_env() -> Lang {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty());
+
+        match locale {
+            None => Lang::Zh,
+            Some(locale) => {
+                let name = locale.split(['.', '@']).next().unwrap_or_default();
+                if name.starts_with("zh") || name == "C" || name == "POSIX" {
+                    Lang::Zh
+                } else {
+                    Lang::En
+                }
+            }
+        }
+    }
+
+    /// 按语言选择文本
+    pub fn pick<'a>(self, zh: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Lang::Zh => zh,
+            Lang::En => en,
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "zh" | "zh-cn" | "zh_cn" | "cn" | "chinese" => Ok(Lang::Zh),
+            "en" | "en-us" | "en_us" | "english" => Ok(Lang::En),
+            _ => Err(tr!("未知的语言: {}（可选 zh、en）", "unknown language: {} (expected zh, en)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.pick("zh", "en"))
+    }
+}
+
+/// 标题行字段的显示名称，`field` 为 `HeaderFieldError::field` 中的字段标识
+pub(crate) fn field_name(field: &str) -> &str {
+    let lang = lang();
+    match field {
+        "header" => lang.pick("标题行", "header"),
+        "comment" => lang.pick("注释", "comment"),
+        "read_name" => lang.pick("读段名", "read name"),
+        "instrument" => lang.pick("仪器编号", "instrument"),
+        "run" => lang.pick("运行编号", "run number"),
+        "flowcell" => lang.pick("流动槽编号", "flow cell"),
+        "x" => lang.pick("x 坐标", "x coordinate"),
+        "y" => lang.pick("y 坐标", "y coordinate"),
+        "read_number" => lang.pick("读段序号", "read number"),
+        "filter_flag" => lang.pick("过滤标记", "filter flag"),
+        "control" => lang.pick("控制编号", "control number"),
+        "index" => lang.pick("index 序列", "index sequence"),
+        "umi" => lang.pick("UMI 序列", "UMI sequence"),
+        "platform" => lang.pick("测序平台", "platform"),
+        "uuid" => lang.pick("读段 UUID", "read UUID"),
+        "subread_range" => lang.pick("读段区间", "subread range"),
+        // lane、tile、runid、ch 等字段名本身即可读
+        other => other,
+    }
+}
+
+/// 错误信息的正文（不含错误代码）
+pub(crate) fn error_message(err: &FastqError) -> String {
+    match err {
+        FastqError::Io(e) => tr!("I/O 错误: {}", "I/O error: {}", e),
+        FastqError::Format(msg) => tr!("格式错误: {}", "format error: {}", msg),
+        FastqError::InvalidHeader(line) => tr!(
+            "标题行 (行 {}) 没有以 @ 开头",
+            "header line (line {}) does not start with @",
+            line
+        ),
+        FastqError::InvalidPlusLine(line) => tr!(
+            "加号行 (行 {}) 没有以 + 开头",
+            "plus line (line {}) does not start with +",
+            line
+        ),
+        FastqError::PlusLineMismatch { line, header, plus } => tr!(
+            "加号行 (行 {line}) 的标识符 \"{plus}\" 与标题行的 \"{header}\" 不一致",
+            "plus line (line {line}) identifier \"{plus}\" does not match the header \"{header}\""
+        ),
+        FastqError::PlusLineNotBare(line) => tr!(
+            "加号行 (行 {}) 不是单独的 +",
+            "plus line (line {}) is not a bare +",
+            line
+        ),
+        FastqError::LengthMismatch { seq_len, qual_len, line_num } => tr!(
+            "序列长度 ({seq_len}) 与质量值长度 ({qual_len}) 不匹配 (行 {line_num})",
+            "sequence length ({seq_len}) does not match quality length ({qual_len}) (line {line_num})"
+        ),
+        FastqError::InvalidBase { line, column, byte } => tr!(
+            "序列 (行 {line}, 第 {column} 列) 含有非法字符 {}",
+            "sequence (line {line}, column {column}) contains invalid character {}",
+            display_byte(*byte)
+        ),
+        FastqError::InvalidQuality { line, column, byte, encoding } => tr!(
+            "质量值 (行 {line}, 第 {column} 列) 的字符 {} 超出 {encoding} 的范围",
+            "quality (line {line}, column {column}) character {} is outside the {encoding} range",
+            display_byte(*byte)
+        ),
+        FastqError::DuplicateReadId { name, first_line, line } => tr!(
+            "读段名 {name} 重复出现 (行 {first_line} 与行 {line})",
+            "read name {name} is duplicated (lines {first_line} and {line})"
+        ),
+        FastqError::PairNameMismatch { r1_line, r2_line, r1_name, r2_name } => tr!(
+            "R1 (行 {r1_line}) 与 R2 (行 {r2_line}) 的读段名不一致: {r1_name} / {r2_name}",
+            "R1 (line {r1_line}) and R2 (line {r2_line}) read names differ: {r1_name} / {r2_name}"
+        ),
+        FastqError::PairCountMismatch { r1_records, r2_records } => tr!(
+            "R1 与 R2 的记录数不同: {r1_records} / {r2_records}",
+            "R1 and R2 have different record counts: {r1_records} / {r2_records}"
+        ),
+        FastqError::OrphanRead { name, line } => tr!(
+            "读段 {name} (行 {line}) 没有配对的另一端",
+            "read {name} (line {line}) has no mate"
+        ),
+        FastqError::MateOrder { name, line, expected, found } => tr!(
+            "读段 {name} (行 {line}) 的读段序号应为 {expected}，实际为 {found}",
+            "read {name} (line {line}) should have read number {expected}, found {found}"
+        ),
+        FastqError::MalformedHeader { line, field, value } if value.is_empty() => tr!(
+            "标题行 (行 {line}) 缺少{}字段",
+            "header (line {line}) is missing the {} field",
+            field_name(field)
+        ),
+        FastqError::MalformedHeader { line, field, value } => tr!(
+            "标题行 (行 {line}) 的{}字段无效: \"{value}\"",
+            "header (line {line}) has an invalid {} field: \"{value}\"",
+            field_name(field)
+        ),
+        FastqError::FilteredRead(line) => tr!(
+            "读段 (行 {}) 的过滤标记为 Y，未通过质控过滤",
+            "read (line {}) has filter flag Y and failed quality filtering",
+            line
+        ),
+        FastqError::IndexLengthMismatch { line, length, expected } => tr!(
+            "index 序列 (行 {line}) 长度为 {length}，与多数读段的 {expected} 不同",
+            "index sequence (line {line}) has length {length}, unlike the majority length {expected}"
+        ),
+        FastqError::TruncatedRecord { line, lines_present } => tr!(
+            "记录不完整 (行 {line} 起): 文件结束时只有 {lines_present} 行",
+            "incomplete record (from line {line}): input ended after {lines_present} lines"
+        ),
+        FastqError::SkippedLines { first, last } => tr!(
+            "行 {first}-{last} 无法组成完整记录，已跳过",
+            "lines {first}-{last} do not form a complete record and were skipped"
+        ),
+        FastqError::ReadTooShort { line, length, min } => tr!(
+            "序列 (行 {line}) 长度为 {length}，短于最小长度 {min}",
+            "sequence (line {line}) has length {length}, shorter than the minimum {min}"
+        ),
+        FastqError::ReadTooLong { line, length, max } => tr!(
+            "序列 (行 {line}) 长度为 {length}，超过最大长度 {max}",
+            "sequence (line {line}) has length {length}, longer than the maximum {max}"
+        ),
+        FastqError::LowercaseBase { line, column, byte } => tr!(
+            "序列 (行 {line}, 第 {column} 列) 含有小写碱基 {}",
+            "sequence (line {line}, column {column}) contains lowercase base {}",
+            display_byte(*byte)
+        ),
+        FastqError::CrlfLineEnding(line) => tr!(
+            "行 {} 使用 Windows 换行符 (\\r\\n)",
+            "line {} uses a Windows line ending (\\r\\n)",
+            line
+        ),
+        FastqError::RuleViolation { rule, line, message } => tr!(
+            "规则 {rule} (行 {line}): {message}",
+            "rule {rule} (line {line}): {message}"
+        ),
+    }
+}
@@ -1,126 +1,316 @@
-use thiserror::Error;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
-
-/// 表示 FASTQ 中的一条序列记录
-#[derive(Debug)]
-pub struct FastqRecord {
-    pub header: String,    // 以 @ 开头的序列标识符
-    pub sequence: String,  // 序列数据
-    pub plus_line: String, // 以 + 开头的行
-    pub quality: String,   // 质量值
-}
-
-/// FASTQ 解析和验证过程中可能发生的错误
-#[derive(Error, Debug)]
-pub enum FastqError {
-    #[error("I/O 错误: {0}")]
-    Io(#[from] std::io::Error),
-    
-    #[error("格式错误: {0}")]
-    Format(String),
-    
-    #[error("标题行 (行 {0}) 没有以 @ 开头")]
-    InvalidHeader(usize),
-    
-    #[error("加号行 (行 {0}) 没有以 + 开头")]
-    InvalidPlusLine(usize),
-    
-    #[error("序列长度 ({seq_len}) 与质量值长度 ({qual_len}) 不匹配 (行 {line_num})")]
-    LengthMismatch { seq_len: usize, qual_len: usize, line_num: usize },
-}
-
-/// 验证 FASTQ 记录是否格式正确
-pub fn validate_record(record: &FastqRecord, line_num: usize) -> Result<(), FastqError> {
-    // 检查标题行是否以 @ 开头
-    if !record.header.starts_with('@') {
-        return Err(FastqError::InvalidHeader(line_num));
-    }
-    
-    // 检查加号行是否以 + 开头
-    if !record.plus_line.starts_with('+') {
-        return Err(FastqError::InvalidPlusLine(line_num + 2));
-    }
-    
-    // 检查序列长度与质量值长度是否一致
-    if record.sequence.len() != record.quality.len() {
-        return Err(FastqError::LengthMismatch {
-            seq_len: record.sequence.len(),
-            qual_len: record.quality.len(),
-            line_num: line_num + 3,
-        });
-    }
-    
-    Ok(())
-}
-/// 解析 FASTQ 文件并验证其格式
-pub fn check_fastq_file<P: AsRef<Path>>(
-    input_path: P,
-    error_output_path: Option<P>,
-) -> Result<(usize, usize), FastqError> {
-    let file = File::open(input_path.as_ref())?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    
-    let mut line_num = 0;
-    let mut record_count = 0;
-    let mut error_count = 0;
-    
-    // 创建错误输出文件（如果需要）
-    let mut error_file = if let Some(path) = error_output_path {
-        Some(File::create(path)?)
-    } else {
-        None
-    };
-    
-    // 逐条读取和验证 FASTQ 记录
-    while let Some(Ok(header)) = lines.next() {
-        line_num += 1;
-        
-        // 尝试读取完整的记录（4行）
-        let sequence = match lines.next() {
-            Some(Ok(seq)) => seq,
-            _ => return Err(FastqError::Format(format!("在行 {} 之后意外结束", line_num))),
-        };
-        
-        let plus_line = match lines.next() {
-            Some(Ok(plus)) => plus,
-            _ => return Err(FastqError::Format(format!("在行 {} 之后意外结束", line_num + 1))),
-        };
-        
-        let quality = match lines.next() {
-            Some(Ok(qual)) => qual,
-            _ => return Err(FastqError::Format(format!("在行 {} 之后意外结束", line_num + 2))),
-        };
-        
-        // 创建和验证记录
-        let record = FastqRecord {
-            header,
-            sequence,
-            plus_line,
-            quality,
-        };
-        
-        record_count += 1;
-        
-        // 验证记录并处理错误
-        if let Err(err) = validate_record(&record, line_num) {
-            error_count += 1;
-            
-            // 如果提供了错误输出文件，则写入错误记录
-            if let Some(ref mut file) = error_file {
-                writeln!(file, "错误: {:?}", err)?;
-                writeln!(file, "{}", record.header)?;
-                writeln!(file, "{}", record.sequence)?;
-                writeln!(file, "{}", record.plus_line)?;
-                writeln!(file, "{}", record.quality)?;
-                writeln!(file, "---")?;
-            }
-        }
-        
-        line_num += 3; // 我们已经处理了4行
-    }
-    
-    Ok((record_count, error_count))
-}
\ No newline at end of file
+use thiserror::Error;
+use std::fmt;
+use std::io::Write;
+
+mod alphabet;
+mod check;
+mod compression;
+mod diagnostic;
+mod duplicates;
+mod header;
+mod i18n;
+mod pair;
+mod profile;
+mod quality;
+mod reader;
+mod report;
+mod rule;
+
+pub use alphabet::Alphabet;
+pub use check::{
+    check_fastq_file, check_fastq_path, check_fastq_reopenable, CheckOptions, ErrorLocation, ValidationSummary,
+};
+pub use compression::{decompress, open_input, Compression};
+pub use diagnostic::Diagnostic;
+pub use duplicates::DuplicateCheck;
+pub use i18n::{lang, set_lang, Lang};
+pub use header::{HeaderFieldError, IlluminaHeader, OntHeader, PacBioHeader, Platform, PlatformHeader};
+pub use pair::{check_fastq_pair, mate_name, read_number, PairSummary};
+pub use profile::{Profile, Severity};
+pub use quality::{QualityDetector, QualityEncoding};
+pub use reader::FastqReader;
+pub use report::ReportFormat;
+pub use rule::{
+    AlphabetRule, FilterFlagRule, HeaderRule, LengthRule, LowercaseRule, PlatformHeaderRule, PlusLineRule, QualityRule,
+    ReadLengthRule, RecordContext, Rule, Validator, Violation,
+};
+
+/// 表示 FASTQ 中的一条序列记录
+#[derive(Debug)]
+pub struct FastqRecord {
+    pub header: String,    // 以 @ 开头的序列标识符
+    pub sequence: String,  // 序列数据
+    pub plus_line: String, // 以 + 开头的行
+    pub quality: String,   // 质量值
+}
+
+impl FastqRecord {
+    /// 以字节视图的形式借用该记录
+    pub fn as_record_ref(&self) -> FastqRecordRef<'_> {
+        FastqRecordRef {
+            header: self.header.as_bytes(),
+            sequence: self.sequence.as_bytes(),
+            plus_line: self.plus_line.as_bytes(),
+            quality: self.quality.as_bytes(),
+        }
+    }
+}
+
+/// 借用读取缓冲区的 FASTQ 记录视图，各字段为不含换行符的原始字节
+#[derive(Debug, Clone, Copy)]
+pub struct FastqRecordRef<'a> {
+    pub header: &'a [u8],
+    pub sequence: &'a [u8],
+    pub plus_line: &'a [u8],
+    pub quality: &'a [u8],
+}
+
+impl<'a> FastqRecordRef<'a> {
+    /// 读段名：标题行去掉 @ 后到第一个空白字符为止的部分
+    pub fn read_id(&self) -> &'a [u8] {
+        header_read_id(self.header)
+    }
+
+    /// 按测序平台解析标题行，得到仪器、通道、ZMW 等字段
+    pub fn platform_header(&self, platform: Platform) -> Result<PlatformHeader<'a>, HeaderFieldError> {
+        PlatformHeader::parse(self.header, platform)
+    }
+
+    /// 记录的 4 行
+    pub fn lines(&self) -> [&'a [u8]; 4] {
+        [self.header, self.sequence, self.plus_line, self.quality]
+    }
+
+    /// 复制为拥有所有权的记录，非 UTF-8 字节会被替换为 U+FFFD
+    pub fn to_record(&self) -> FastqRecord {
+        FastqRecord {
+            header: String::from_utf8_lossy(self.header).into_owned(),
+            sequence: String::from_utf8_lossy(self.sequence).into_owned(),
+            plus_line: String::from_utf8_lossy(self.plus_line).into_owned(),
+            quality: String::from_utf8_lossy(self.quality).into_owned(),
+        }
+    }
+
+    /// 将记录按原始的 4 行格式写出
+    pub fn write_to<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        for line in self.lines() {
+            out.write_all(line)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// 标题行去掉 @ 后到第一个空白字符为止的部分
+pub(crate) fn header_read_id(header: &[u8]) -> &[u8] {
+    let name = header.strip_prefix(b"@").unwrap_or(header);
+    let end = name.iter().position(|b| b.is_ascii_whitespace()).unwrap_or(name.len());
+    &name[..end]
+}
+
+/// FASTQ 解析和验证过程中可能发生的错误
+///
+/// 显示为 `[FQ005] 信息`，信息的语言由 `set_lang` 决定，错误代码与语言无关。
+#[derive(Error, Debug)]
+pub enum FastqError {
+    Io(#[from] std::io::Error),
+    Format(String),
+    InvalidHeader(usize),
+    InvalidPlusLine(usize),
+    PlusLineMismatch { line: usize, header: String, plus: String },
+    PlusLineNotBare(usize),
+    LengthMismatch { seq_len: usize, qual_len: usize, line_num: usize },
+    InvalidBase { line: usize, column: usize, byte: u8 },
+    InvalidQuality { line: usize, column: usize, byte: u8, encoding: QualityEncoding },
+    DuplicateReadId { name: String, first_line: usize, line: usize },
+    PairNameMismatch { r1_line: usize, r2_line: usize, r1_name: String, r2_name: String },
+    PairCountMismatch { r1_records: usize, r2_records: usize },
+    OrphanRead { name: String, line: usize },
+    MateOrder { name: String, line: usize, expected: u8, found: u8 },
+    MalformedHeader { line: usize, field: &'static str, value: String },
+    FilteredRead(usize),
+    IndexLengthMismatch { line: usize, length: usize, expected: usize },
+    TruncatedRecord { line: usize, lines_present: usize },
+    SkippedLines { first: usize, last: usize },
+    /// 自定义规则发现的问题，`rule` 为 `Rule::name`
+    RuleViolation { rule: &'static str, line: usize, message: String },
+    ReadTooShort { line: usize, length: usize, min: usize },
+    ReadTooLong { line: usize, length: usize, max: usize },
+    LowercaseBase { line: usize, column: usize, byte: u8 },
+    CrlfLineEnding(usize),
+}
+
+impl fmt::Display for FastqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.code(), i18n::error_message(self))
+    }
+}
+
+impl FastqError {
+    /// 所有内置错误类别的（错误代码，类别名称）
+    ///
+    /// 自定义规则的错误代码都是 `FQ018`，类别名称为规则名称。
+    pub const KINDS: &'static [(&'static str, &'static str)] = &[
+        ("FQ001", "invalid_header"),
+        ("FQ002", "invalid_plus_line"),
+        ("FQ003", "plus_line_mismatch"),
+        ("FQ004", "plus_line_not_bare"),
+        ("FQ005", "length_mismatch"),
+        ("FQ006", "invalid_base"),
+        ("FQ007", "invalid_quality"),
+        ("FQ008", "duplicate_read_id"),
+        ("FQ009", "pair_name_mismatch"),
+        ("FQ010", "pair_count_mismatch"),
+        ("FQ011", "orphan_read"),
+        ("FQ012", "mate_order"),
+        ("FQ013", "malformed_header"),
+        ("FQ014", "filtered_read"),
+        ("FQ015", "index_length_mismatch"),
+        ("FQ016", "truncated_record"),
+        ("FQ017", "skipped_lines"),
+        ("FQ019", "read_too_short"),
+        ("FQ020", "read_too_long"),
+        ("FQ021", "lowercase_base"),
+        ("FQ022", "crlf_line_ending"),
+        ("FQ101", "io"),
+        ("FQ102", "format"),
+    ];
+
+    /// 按类别名称或错误代码（不区分大小写）查找错误类别，返回类别名称
+    pub fn kind_by_name(name: &str) -> Option<&'static str> {
+        FastqError::KINDS
+            .iter()
+            .find(|(code, kind)| code.eq_ignore_ascii_case(name) || kind.eq_ignore_ascii_case(name))
+            .map(|&(_, kind)| kind)
+    }
+
+    /// 稳定的错误代码，如 `FQ001`，用于报告和筛选
+    pub fn code(&self) -> &'static str {
+        match self {
+            FastqError::InvalidHeader(_) => "FQ001",
+            FastqError::InvalidPlusLine(_) => "FQ002",
+            FastqError::PlusLineMismatch { .. } => "FQ003",
+            FastqError::PlusLineNotBare(_) => "FQ004",
+            FastqError::LengthMismatch { .. } => "FQ005",
+            FastqError::InvalidBase { .. } => "FQ006",
+            FastqError::InvalidQuality { .. } => "FQ007",
+            FastqError::DuplicateReadId { .. } => "FQ008",
+            FastqError::PairNameMismatch { .. } => "FQ009",
+            FastqError::PairCountMismatch { .. } => "FQ010",
+            FastqError::OrphanRead { .. } => "FQ011",
+            FastqError::MateOrder { .. } => "FQ012",
+            FastqError::MalformedHeader { .. } => "FQ013",
+            FastqError::FilteredRead(_) => "FQ014",
+            FastqError::IndexLengthMismatch { .. } => "FQ015",
+            FastqError::TruncatedRecord { .. } => "FQ016",
+            FastqError::SkippedLines { .. } => "FQ017",
+            FastqError::RuleViolation { .. } => "FQ018",
+            FastqError::ReadTooShort { .. } => "FQ019",
+            FastqError::ReadTooLong { .. } => "FQ020",
+            FastqError::LowercaseBase { .. } => "FQ021",
+            FastqError::CrlfLineEnding(_) => "FQ022",
+            FastqError::Io(_) => "FQ101",
+            FastqError::Format(_) => "FQ102",
+        }
+    }
+
+    /// 错误类别的名称（snake_case），如 `invalid_header`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FastqError::InvalidHeader(_) => "invalid_header",
+            FastqError::InvalidPlusLine(_) => "invalid_plus_line",
+            FastqError::PlusLineMismatch { .. } => "plus_line_mismatch",
+            FastqError::PlusLineNotBare(_) => "plus_line_not_bare",
+            FastqError::LengthMismatch { .. } => "length_mismatch",
+            FastqError::InvalidBase { .. } => "invalid_base",
+            FastqError::InvalidQuality { .. } => "invalid_quality",
+            FastqError::DuplicateReadId { .. } => "duplicate_read_id",
+            FastqError::PairNameMismatch { .. } => "pair_name_mismatch",
+            FastqError::PairCountMismatch { .. } => "pair_count_mismatch",
+            FastqError::OrphanRead { .. } => "orphan_read",
+            FastqError::MateOrder { .. } => "mate_order",
+            FastqError::MalformedHeader { .. } => "malformed_header",
+            FastqError::FilteredRead(_) => "filtered_read",
+            FastqError::IndexLengthMismatch { .. } => "index_length_mismatch",
+            FastqError::TruncatedRecord { .. } => "truncated_record",
+            FastqError::SkippedLines { .. } => "skipped_lines",
+            FastqError::RuleViolation { rule, .. } => rule,
+            FastqError::ReadTooShort { .. } => "read_too_short",
+            FastqError::ReadTooLong { .. } => "read_too_long",
+            FastqError::LowercaseBase { .. } => "lowercase_base",
+            FastqError::CrlfLineEnding(_) => "crlf_line_ending",
+            FastqError::Io(_) => "io",
+            FastqError::Format(_) => "format",
+        }
+    }
+
+    /// 出错的行号；成对检查的错误取 R1 的行号，与具体行无关的错误返回 `None`
+    pub fn line(&self) -> Option<usize> {
+        match *self {
+            FastqError::InvalidHeader(line)
+            | FastqError::InvalidPlusLine(line)
+            | FastqError::PlusLineNotBare(line)
+            | FastqError::FilteredRead(line)
+            | FastqError::CrlfLineEnding(line)
+            | FastqError::PlusLineMismatch { line, .. }
+            | FastqError::LengthMismatch { line_num: line, .. }
+            | FastqError::InvalidBase { line, .. }
+            | FastqError::InvalidQuality { line, .. }
+            | FastqError::DuplicateReadId { line, .. }
+            | FastqError::PairNameMismatch { r1_line: line, .. }
+            | FastqError::OrphanRead { line, .. }
+            | FastqError::MateOrder { line, .. }
+            | FastqError::MalformedHeader { line, .. }
+            | FastqError::IndexLengthMismatch { line, .. }
+            | FastqError::TruncatedRecord { line, .. }
+            | FastqError::RuleViolation { line, .. }
+            | FastqError::ReadTooShort { line, .. }
+            | FastqError::ReadTooLong { line, .. }
+            | FastqError::LowercaseBase { line, .. }
+            | FastqError::SkippedLines { first: line, .. } => Some(line),
+            FastqError::PairCountMismatch { .. } | FastqError::Io(_) | FastqError::Format(_) => None,
+        }
+    }
+}
+
+/// 以可读形式显示单个字节，不可打印字符显示为转义序列
+fn display_byte(byte: u8) -> String {
+    format!("'{}'", byte.escape_ascii())
+}
+
+/// 验证 FASTQ 记录是否格式正确
+pub fn validate_record(record: &FastqRecord, line_num: usize) -> Result<(), FastqError> {
+    validate_record_ref(&record.as_record_ref(), line_num)
+}
+
+/// 验证借用形式的 FASTQ 记录，`line_num` 为标题行行号
+pub fn validate_record_ref(record: &FastqRecordRef<'_>, line_num: usize) -> Result<(), FastqError> {
+    validate_record_with(record, line_num, &CheckOptions::default())
+}
+
+/// 按给定选项验证 FASTQ 记录，`line_num` 为标题行行号，只返回第一个错误
+///
+/// 严重程度不是 `Severity::Error` 的问题不影响结果。
+pub fn validate_record_with(
+    record: &FastqRecordRef<'_>,
+    line_num: usize,
+    options: &CheckOptions,
+) -> Result<(), FastqError> {
+    let violations = validate_record_all(record, line_num, options);
+    match violations.into_iter().find(|v| v.severity == Severity::Error) {
+        Some(violation) => Err(violation.error),
+        None => Ok(()),
+    }
+}
+
+/// 按给定选项验证 FASTQ 记录，返回 `CheckOptions::validator` 中各条规则发现的所有问题（包括警告）及其严重程度
+///
+/// 使用内置规则时，错误的顺序与 `validate_record_with` 检查的顺序相同。`Severity::Off` 的问题不返回。
+pub fn validate_record_all(
+    record: &FastqRecordRef<'_>,
+    line_num: usize,
+    options: &CheckOptions,
+) -> Vec<Violation> {
+    let ctx = RecordContext::new(record, line_num, options);
+    options.validator.validate(record, &ctx)
+}
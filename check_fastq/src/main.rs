@@ -1,10 +1,34 @@
-use std::path::PathBuf;
-use clap::{Parser, Subcommand};
-use check_fastq::{check_fastq_file, FastqError};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
+use clap::error::ErrorKind;
+use clap::{Args, Command, CommandFactory, FromArgMatches, Parser, Subcommand};
+use check_fastq::{
+    check_fastq_file, check_fastq_pair, check_fastq_reopenable, Alphabet, CheckOptions,
+    DuplicateCheck, ErrorLocation, FastqError, Validator, Lang, lang, Platform, Profile, QualityEncoding, ReportFormat,
+    PairSummary, Severity, ValidationSummary, tr,
+};
+use serde_json::{json, Map, Value};
+
+/// 发现会导致失败的验证错误
+const EXIT_INVALID: u8 = 1;
+/// 读写文件出错
+const EXIT_IO: u8 = 2;
+/// 命令行参数有误
+const EXIT_USAGE: u8 = 3;
+/// 输入在记录中途结束
+const EXIT_TRUNCATED: u8 = 4;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    /// 输出信息的语言: zh、en，不指定时根据 LANG 等环境变量选择
+    #[arg(long, global = true)]
+    lang: Option<Lang>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -12,45 +36,794 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// 检查 FASTQ 文件格式
-    Check {
-        /// 输入的 FASTQ 文件路径
-        #[arg(short, long)]
-        input: PathBuf,
-        
-        /// 错误输出文件路径（可选）
-        #[arg(short, long)]
-        output: Option<PathBuf>,
-    },
-}
-
-fn main() -> Result<(), FastqError> {
-    let cli = Cli::parse();
-    
-    match &cli.command {
-        Commands::Check { input, output } => {
-            println!("正在检查 FASTQ 文件: {}", input.display());
-            
-            match check_fastq_file(input, output.as_ref()) {
-                Ok((record_count, error_count)) => {
-                    println!("检查完成！");
-                    println!("处理的记录总数: {}", record_count);
-                    
-                    if error_count == 0 {
-                        println!("未发现错误。");
-                    } else {
-                        println!("发现 {} 条错误记录。", error_count);
-                        if let Some(out_path) = output {
-                            println!("错误记录已写入: {}", out_path.display());
-                        }
-                    }
-                    
-                    Ok(())
-                }
-                Err(e) => {
-                    eprintln!("处理文件时出错: {}", e);
-                    Err(e)
-                }
-            }
-        }
-    }
-}
\ No newline at end of file
+    Check(CheckArgs),
+    /// 同步检查双端测序的 R1、R2 文件
+    CheckPair(PairArgs),
+}
+
+/// 命令行帮助的英文文本（子命令或参数名, 说明），中文说明即各项的文档注释
+const HELP_EN: &[(&str, &str)] = &[
+    ("check", "Check the format of a FASTQ file"),
+    ("check-pair", "Check paired-end R1 and R2 files in step"),
+    ("lang", "Language of messages: zh, en; chosen from LANG and related variables when omitted"),
+    ("input", "Input FASTQ file, `-` for stdin"),
+    ("output", "Error output file (optional), `-` for stdout"),
+    ("interleaved", "Input is interleaved paired-end data: adjacent records are the two mates of a fragment"),
+    ("check_index_length", "Report reads whose Illumina index length differs from the majority"),
+    ("r1", "R1 file, `-` for stdin (only one of R1 and R2 may be stdin)"),
+    ("r2", "R2 file, `-` for stdin"),
+    ("resync", "After a misframed record (extra or missing lines), skip to the next complete record and go on"),
+    ("profile", "Built-in validation profile: default, strict, lenient; other command-line options take precedence"),
+    (
+        "config",
+        "Read the validation profile (rules and severities, alphabet, length range, ...) from a TOML file \
+         instead of --profile",
+    ),
+    ("alphabet", "Allowed sequence alphabet: any, strict (ACGTN), iupac (default), rna"),
+    ("case_sensitive", "Bases are case-sensitive: lowercase bases are errors"),
+    ("require_bare_plus", "Require the plus line to be a bare +, without repeating the identifier"),
+    (
+        "quality_encoding",
+        "Quality encoding: phred33, phred64, solexa64; when omitted only printable characters are required \
+         and the inferred encoding is reported",
+    ),
+    (
+        "duplicates",
+        "Detect duplicate read names: off (default), exact (memory grows with the reads), \
+         bloom (fixed memory, reads the input twice)",
+    ),
+    (
+        "platform",
+        "Validate header fields against a sequencing platform: auto (from the first record), illumina, ont, pacbio",
+    ),
+    ("flag_filtered", "Report reads whose Illumina filter flag is Y (failed the quality filter)"),
+    (
+        "report_format",
+        "Error output format: text, jsonl (one JSON object per line), tsv, pretty (diagnostics marking the error)",
+    ),
+    ("min_length", "Minimum sequence length"),
+    ("max_length", "Maximum sequence length"),
+    (
+        "fail_on",
+        "Only these error or warning kinds make the check fail (kind names, codes or rule names, \
+         comma-separated); by default the kinds whose severity is error",
+    ),
+    ("max_errors", "Stop checking after this many errors (at least 1)"),
+    ("max_errors_per_kind", "Write at most N detailed records per error kind, count the rest"),
+    ("fail_fast", "Stop at the first error, same as --max-errors 1"),
+    ("summary_json", "Write the run summary as JSON to this file for workflow managers"),
+    (
+        "multiqc",
+        "Write the run summary as a MultiQC custom-content file (the name must end in _mqc.json for MultiQC)",
+    ),
+    ("bloom_memory", "Memory limit of the bloom mode in MiB, 512 by default"),
+];
+
+/// 按当前语言设置帮助文本的命令行定义
+fn command() -> Command {
+    let command = Cli::command();
+    if lang() == Lang::Zh {
+        return command;
+    }
+    let command = localize_args(command);
+    let names: Vec<String> = command.get_subcommands().map(|sub| sub.get_name().to_string()).collect();
+    names.iter().fold(command, |command, name| {
+        command.mut_subcommand(name, |sub| {
+            let sub = match help_en(name) {
+                Some(about) => sub.about(about),
+                None => sub,
+            };
+            localize_args(sub)
+        })
+    })
+}
+
+/// 把命令的各个参数的说明换成英文
+fn localize_args(command: Command) -> Command {
+    let ids: Vec<String> = command.get_arguments().map(|arg| arg.get_id().to_string()).collect();
+    ids.iter().fold(command, |command, id| match help_en(id) {
+        Some(help) => command.mut_arg(id, |arg| arg.help(help)),
+        None => command,
+    })
+}
+
+fn help_en(name: &str) -> Option<&'static str> {
+    HELP_EN.iter().find(|&&(key, _)| key == name).map(|&(_, help)| help)
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// 输入的 FASTQ 文件路径，`-` 表示标准输入
+    #[arg(short, long)]
+    input: PathBuf,
+
+    /// 错误输出文件路径（可选），`-` 表示标准输出
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// 输入为交错格式的双端数据，相邻两条记录是同一片段的两端
+    #[arg(long)]
+    interleaved: bool,
+
+    /// 报告 Illumina index 序列长度与多数读段不同的读段
+    #[arg(long)]
+    check_index_length: bool,
+
+    #[command(flatten)]
+    validation: ValidationArgs,
+}
+
+#[derive(Args)]
+struct PairArgs {
+    /// R1 文件路径，`-` 表示标准输入（R1、R2 只能有一个是标准输入）
+    #[arg(short = '1', long)]
+    r1: PathBuf,
+
+    /// R2 文件路径，`-` 表示标准输入
+    #[arg(short = '2', long)]
+    r2: PathBuf,
+
+    /// 错误输出文件路径（可选），`-` 表示标准输出
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    validation: ValidationArgs,
+}
+
+/// 单文件与成对检查共用的验证选项
+#[derive(Args)]
+struct ValidationArgs {
+    /// 记录错位（多出或缺少行）后跳到下一条完整记录继续检查
+    #[arg(long)]
+    resync: bool,
+
+    /// 内置的验证配置: default、strict、lenient，命令行中的其他选项优先
+    #[arg(long, default_value = "default")]
+    profile: Profile,
+
+    /// 从 TOML 文件读取验证配置（启用的规则与严重程度、字母表、长度范围等），代替 --profile
+    #[arg(long, conflicts_with = "profile")]
+    config: Option<PathBuf>,
+
+    /// 序列允许的字母表: any、strict (ACGTN)、iupac（默认）、rna
+    #[arg(long)]
+    alphabet: Option<Alphabet>,
+
+    /// 碱基区分大小写，小写碱基视为错误
+    #[arg(long)]
+    case_sensitive: bool,
+
+    /// 要求加号行只有一个 +，不允许重复标识符
+    #[arg(long)]
+    require_bare_plus: bool,
+
+    /// 质量值编码: phred33、phred64、solexa64，不指定时只检查是否为可打印字符，并报告推断出的编码
+    #[arg(long)]
+    quality_encoding: Option<QualityEncoding>,
+
+    /// 检测重复的读段名: off（默认）、exact（精确，内存随读段数增长）、bloom（固定内存，需要再读一遍输入）
+    #[arg(long)]
+    duplicates: Option<DuplicateCheck>,
+
+    /// 按测序平台的格式验证标题行的各个字段: auto（根据第一条记录识别）、illumina、ont、pacbio
+    #[arg(long)]
+    platform: Option<Platform>,
+
+    /// 报告 Illumina 过滤标记为 Y（未通过质控过滤）的读段
+    #[arg(long)]
+    flag_filtered: bool,
+
+    /// 错误输出的格式: text、jsonl（每行一个 JSON 对象）、tsv、pretty（标出出错位置的诊断信息）
+    #[arg(long, default_value_t = ReportFormat::Text)]
+    report_format: ReportFormat,
+
+    /// 序列的最小长度
+    #[arg(long, value_name = "N")]
+    min_length: Option<usize>,
+
+    /// 序列的最大长度
+    #[arg(long, value_name = "N")]
+    max_length: Option<usize>,
+
+    /// 只有这些类别的错误或警告才使检查失败（类别名称、错误代码或规则名称，逗号分隔），默认为配置中严重程度为 error 的类别
+    #[arg(long, value_delimiter = ',', value_parser = parse_error_kind)]
+    fail_on: Vec<&'static str>,
+
+    /// 发现这么多错误后停止检查（至少为 1）
+    #[arg(long, value_name = "N", value_parser = parse_positive)]
+    max_errors: Option<usize>,
+
+    /// 每类错误最多写出 N 条详细记录，超过后只计数
+    #[arg(long, value_name = "N")]
+    max_errors_per_kind: Option<usize>,
+
+    /// 遇到第一个错误就停止检查，相当于 --max-errors 1
+    #[arg(long, conflicts_with = "max_errors")]
+    fail_fast: bool,
+
+    /// 将统计结果以 JSON 格式写入该文件，供流程管理工具读取
+    #[arg(long)]
+    summary_json: Option<PathBuf>,
+
+    /// 将统计结果写成 MultiQC 自定义内容文件（文件名需以 _mqc.json 结尾才会被 MultiQC 识别）
+    #[arg(long)]
+    multiqc: Option<PathBuf>,
+
+    /// bloom 模式使用的内存上限 (MiB)，默认 512
+    #[arg(long)]
+    bloom_memory: Option<usize>,
+}
+
+impl ValidationArgs {
+    /// 命令行参数对应的检查选项，`output` 为错误输出的路径
+    ///
+    /// 先应用 `--config` 或 `--profile` 的配置，再应用命令行中指定的选项。
+    fn options(&self, output: Option<&Path>) -> CheckOptions {
+        let mut options = CheckOptions {
+            resync: self.resync,
+            report_format: self.report_format,
+            color: use_color(output),
+            ..CheckOptions::default()
+        };
+        self.profile().apply(&mut options);
+
+        // 开关型的选项只能打开，不能关闭配置中打开的项
+        options.case_sensitive |= self.case_sensitive;
+        options.require_bare_plus |= self.require_bare_plus;
+        options.flag_filtered |= self.flag_filtered;
+        if let Some(alphabet) = self.alphabet {
+            options.alphabet = alphabet;
+        }
+        if let Some(duplicates) = self.duplicates {
+            options.duplicates = duplicates;
+        }
+        if let (DuplicateCheck::Bloom { memory }, Some(mib)) = (&mut options.duplicates, self.bloom_memory) {
+            *memory = mib * 1024 * 1024;
+        }
+        options.quality_encoding = self.quality_encoding.or(options.quality_encoding);
+        options.platform = self.platform.or(options.platform);
+        options.min_length = self.min_length.or(options.min_length);
+        options.max_length = self.max_length.or(options.max_length);
+        options.max_errors = if self.fail_fast { Some(1) } else { self.max_errors.or(options.max_errors) };
+        options.max_errors_per_kind = self.max_errors_per_kind.or(options.max_errors_per_kind);
+        options
+    }
+
+    /// `--config` 文件中的配置，没有时为 `--profile` 选择的内置配置
+    fn profile(&self) -> Profile {
+        let Some(path) = &self.config else {
+            return self.profile.clone();
+        };
+        let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            let message = tr!("无法读取配置文件 {}: {}", "cannot read configuration {}: {}", path.display(), e);
+            usage_error(ErrorKind::Io, &message)
+        });
+        Profile::from_toml(&text).unwrap_or_else(|e| {
+            usage_error(ErrorKind::InvalidValue, &format!("{}: {}", path.display(), e))
+        })
+    }
+}
+
+/// 错误类别、错误代码或规则名称，返回类别名称或规则名称
+///
+/// 读写错误（`io`）和无法识别的输入（`format`）直接以 `EXIT_IO` 结束，不会计入错误统计，因此不能选择。
+fn parse_error_kind(name: &str) -> Result<&'static str, String> {
+    let failable = |kind: &&str| !matches!(*kind, "io" | "format");
+    let validator = Validator::new();
+    let found = FastqError::kind_by_name(name)
+        .filter(failable)
+        .or_else(|| validator.rule_names().find(|&rule| rule == name));
+    found.ok_or_else(|| {
+        let kinds: Vec<&str> = FastqError::KINDS.iter().map(|&(_, kind)| kind).filter(failable).collect();
+        tr!("未知的错误类别: {}（可选 {}）", "unknown error kind: {} (expected {})", name, kinds.join(", "))
+    })
+}
+
+fn parse_positive(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(0) | Err(_) => Err(tr!("应为正整数: {}", "expected a positive integer: {}", s)),
+        Ok(n) => Ok(n),
+    }
+}
+
+/// 报告命令行参数错误并以 `EXIT_USAGE` 退出
+fn usage_error(kind: ErrorKind, message: &str) -> ! {
+    let _ = command().error(kind, message).print();
+    process::exit(EXIT_USAGE.into())
+}
+
+/// 根据各类错误与警告的数量选出导致检查失败的类别
+///
+/// 指定了 `--fail-on` 时只有选中的类别（可以是警告）会导致失败，否则为出现过严重程度是 error 的问题的类别。
+fn failing_kinds(
+    errors_by_kind: &BTreeMap<&'static str, usize>,
+    warnings_by_kind: &BTreeMap<&'static str, usize>,
+    args: &ValidationArgs,
+    options: &CheckOptions,
+) -> Vec<&'static str> {
+    // 规则名称展开为该规则报告的所有类别
+    let selected: Vec<&str> = args
+        .fail_on
+        .iter()
+        .flat_map(|name| options.validator.resolve_kinds(name).unwrap_or_default())
+        .collect();
+    // 问题的严重程度可能由规则自行决定，按实际计入错误还是警告判断
+    if args.fail_on.is_empty() {
+        return errors_by_kind.iter().filter(|&(_, &count)| count > 0).map(|(&kind, _)| kind).collect();
+    }
+    let mut failing = Vec::new();
+    for (&kind, &count) in errors_by_kind.iter().chain(warnings_by_kind) {
+        if count > 0 && selected.contains(&kind) && !failing.contains(&kind) {
+            failing.push(kind);
+        }
+    }
+    failing
+}
+
+/// 根据导致失败的类别决定退出码，输入被截断优先于其他验证错误
+fn exit_status(failing: &[&str]) -> ExitCode {
+    if failing.contains(&"truncated_record") {
+        ExitCode::from(EXIT_TRUNCATED)
+    } else if !failing.is_empty() {
+        ExitCode::from(EXIT_INVALID)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 错误记录写到终端时使用颜色，设置了 `NO_COLOR` 环境变量时不使用
+fn use_color(output: Option<&Path>) -> bool {
+    output.is_some_and(is_stdio) && io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// 路径是否为 `-`（标准输入/输出）
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// 打开输入文件，`-` 为标准输入
+fn open_path(path: &Path) -> io::Result<Box<dyn Read + Send>> {
+    if is_stdio(path) {
+        Ok(Box::new(io::stdin()))
+    } else {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+/// 摘要信息的输出位置与错误记录的输出位置
+type Outputs = (Box<dyn Write>, Option<Box<dyn Write>>);
+
+/// 打开错误输出，同时返回摘要信息的输出位置
+///
+/// 错误记录写到标准输出时，摘要改写到标准错误，避免混在一起。
+fn open_outputs(output: Option<&Path>) -> io::Result<Outputs> {
+    match output {
+        Some(path) if is_stdio(path) => Ok((Box::new(io::stderr()), Some(Box::new(io::stdout())))),
+        Some(path) => Ok((Box::new(io::stdout()), Some(Box::new(File::create(path)?)))),
+        None => Ok((Box::new(io::stdout()), None)),
+    }
+}
+
+/// 输出单个文件的统计结果
+fn write_summary(
+    log: &mut dyn Write,
+    summary: &ValidationSummary,
+    options: &CheckOptions,
+) -> io::Result<()> {
+    writeln!(log, "{}", tr!("处理的记录总数: {}", "Records processed: {}", summary.records))?;
+    writeln!(log, "{}", tr!("碱基总数: {}", "Total bases: {}", summary.bases))?;
+    match summary.read_length_range {
+        Some((min, max)) if min == max => writeln!(log, "{}", tr!("读段长度: {}", "Read length: {}", min))?,
+        Some((min, max)) => writeln!(log, "{}", tr!("读段长度: {}-{}", "Read length: {}-{}", min, max))?,
+        None => {}
+    }
+    if let Some(first) = summary.first_error
+        && let Some(line) = first.line
+    {
+        writeln!(
+            log,
+            "{}",
+            tr!("第一个错误: 行 {} [{}] {}", "First error: line {} [{}] {}", line, first.code, first.kind)
+        )?;
+    }
+
+    if let Some(encoding) = summary.quality_encoding {
+        if options.quality_encoding.is_some() {
+            writeln!(log, "{}", tr!("质量值编码: {}", "Quality encoding: {}", encoding))?;
+        } else {
+            writeln!(log, "{}", tr!("质量值编码: {} (自动检测)", "Quality encoding: {} (detected)", encoding))?;
+        }
+    }
+
+    let platform = match (options.platform, summary.platform) {
+        (Some(Platform::Auto), Some(platform)) => {
+            tr!("测序平台: {} (自动识别)", "Platform: {} (detected)", platform)
+        }
+        (Some(Platform::Auto), None) if summary.records > 0 => {
+            tr!("测序平台: 无法识别，未检查标题行", "Platform: not recognized, headers not checked")
+        }
+        (_, Some(platform)) => tr!("测序平台: {}", "Platform: {}", platform),
+        _ => return Ok(()),
+    };
+    writeln!(log, "{}", platform)?;
+    Ok(())
+}
+
+/// 输出错误与警告总数以及错误记录的去向
+fn write_error_count(
+    log: &mut dyn Write,
+    errors: usize,
+    warnings: usize,
+    output: Option<&Path>,
+) -> io::Result<()> {
+    if errors == 0 {
+        writeln!(log, "{}", tr!("未发现错误。", "No errors found."))?;
+    } else {
+        writeln!(log, "{}", tr!("发现 {} 个错误。", "Found {} errors.", errors))?;
+    }
+    if warnings > 0 {
+        writeln!(log, "{}", tr!("发现 {} 个警告。", "Found {} warnings.", warnings))?;
+    }
+    if errors + warnings > 0
+        && let Some(out_path) = output
+    {
+        if is_stdio(out_path) {
+            writeln!(log, "{}", tr!("错误记录已写入: 标准输出", "Errors written to: stdout"))?;
+        } else {
+            writeln!(log, "{}", tr!("错误记录已写入: {}", "Errors written to: {}", out_path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// 说明检查或错误输出因错误上限而不完整
+fn write_truncation(
+    log: &mut dyn Write,
+    stopped_early: bool,
+    unreported: usize,
+    options: &CheckOptions,
+) -> io::Result<()> {
+    if stopped_early {
+        let max = options.max_errors.unwrap_or_default();
+        writeln!(
+            log,
+            "{}",
+            tr!(
+                "已达到错误上限 ({})，检查提前停止，之后的记录未检查。",
+                "Error limit ({}) reached: checking stopped early and the remaining records were not checked.",
+                max
+            )
+        )?;
+    }
+    if unreported > 0 {
+        writeln!(
+            log,
+            "{}",
+            tr!(
+                "错误输出不完整: {} 条错误超过每类 {} 条的上限，只计数未写出。",
+                "Error report truncated: {} errors exceeded the limit of {} per kind and were counted but not written.",
+                unreported,
+                options.max_errors_per_kind.unwrap_or_default()
+            )
+        )?;
+    }
+    Ok(())
+}
+
+/// 输入在报告中的名称
+fn input_name(path: &Path) -> String {
+    if is_stdio(path) {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
+/// MultiQC 中的样本名：文件名去掉 FASTQ 和压缩格式的扩展名
+fn sample_name(path: &Path) -> String {
+    if is_stdio(path) {
+        return "stdin".to_string();
+    }
+    let mut name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    for ext in [".gz", ".bgz", ".bz2", ".xz", ".zst", ".fastq", ".fq"] {
+        if let Some(stem) = name.strip_suffix(ext) {
+            name = stem.to_string();
+        }
+    }
+    name
+}
+
+/// JSON 摘要与 MultiQC 中的结论，与退出码使用同一组导致失败的类别
+fn verdict(failing: &[&str]) -> &'static str {
+    if failing.is_empty() { "pass" } else { "fail" }
+}
+
+/// 单个文件统计结果的 JSON 形式
+fn summary_fields(summary: &ValidationSummary, options: &CheckOptions) -> Map<String, Value> {
+    let detected = options.quality_encoding.is_none() && summary.quality_encoding.is_some();
+    let Value::Object(fields) = json!({
+        "records": summary.records,
+        "bases": summary.bases,
+        "read_length_range": summary.read_length_range.map(|(min, max)| json!({ "min": min, "max": max })),
+        "errors": summary.errors,
+        "errors_by_kind": summary.errors_by_kind,
+        "first_error": summary.first_error.map(first_error_json),
+        "warnings": summary.warnings,
+        "warnings_by_kind": summary.warnings_by_kind,
+        "bytes_read": summary.bytes_read,
+        "quality_encoding": summary.quality_encoding.map(|e| e.to_string()),
+        "quality_encoding_detected": detected,
+        "platform": summary.platform.map(|p| p.to_string()),
+        "stopped_early": summary.stopped_early,
+        "unreported_errors": summary.unreported_errors,
+    }) else {
+        unreachable!()
+    };
+    fields
+}
+
+fn first_error_json(first: ErrorLocation) -> Value {
+    json!({ "code": first.code, "kind": first.kind, "line": first.line })
+}
+
+/// MultiQC 表格中的一行
+fn multiqc_row(summary: &ValidationSummary, verdict: &str) -> Value {
+    json!({
+        "records": summary.records,
+        "bases": summary.bases,
+        "errors": summary.errors,
+        "warnings": summary.warnings,
+        "bytes_read": summary.bytes_read,
+        "quality_encoding": summary.quality_encoding.map(|e| e.to_string()).unwrap_or_default(),
+        "verdict": verdict,
+    })
+}
+
+/// MultiQC 自定义内容（表格），每个输入文件一行
+fn multiqc_content(rows: Map<String, Value>) -> Value {
+    json!({
+        "id": "check_fastq",
+        "section_name": "check_fastq",
+        "description": tr!("FASTQ 格式检查结果", "FASTQ format validation results"),
+        "plot_type": "table",
+        "pconfig": {
+            "id": "check_fastq_table",
+            "title": "check_fastq",
+        },
+        "data": rows,
+    })
+}
+
+fn write_json(path: &Path, value: &Value) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    serde_json::to_writer_pretty(&mut file, value)?;
+    writeln!(file)
+}
+
+/// 按命令行参数写出单个文件的 JSON 摘要和 MultiQC 文件
+fn write_check_reports(
+    args: &CheckArgs,
+    options: &CheckOptions,
+    summary: &ValidationSummary,
+    verdict: &str,
+) -> io::Result<()> {
+    if let Some(path) = &args.validation.summary_json {
+        let mut report = Map::new();
+        report.insert("input".into(), json!(input_name(&args.input)));
+        report.insert("verdict".into(), json!(verdict));
+        report.insert("elapsed_seconds".into(), json!(summary.elapsed.as_secs_f64()));
+        report.extend(summary_fields(summary, options));
+        write_json(path, &Value::Object(report))?;
+    }
+
+    if let Some(path) = &args.validation.multiqc {
+        let mut rows = Map::new();
+        rows.insert(sample_name(&args.input), multiqc_row(summary, verdict));
+        write_json(path, &multiqc_content(rows))?;
+    }
+    Ok(())
+}
+
+/// 按命令行参数写出成对检查的 JSON 摘要和 MultiQC 文件
+fn write_pair_reports(
+    args: &PairArgs,
+    options: &CheckOptions,
+    summary: &PairSummary,
+    verdict: &str,
+) -> io::Result<()> {
+    if let Some(path) = &args.validation.summary_json {
+        let report = json!({
+            "r1_input": input_name(&args.r1),
+            "r2_input": input_name(&args.r2),
+            "verdict": verdict,
+            "elapsed_seconds": summary.r1.elapsed.as_secs_f64(),
+            "errors": summary.errors(),
+            "warnings": summary.warnings(),
+            "pair_errors": summary.pair_errors,
+            "pair_warnings": summary.pair_warnings,
+            "errors_by_kind": summary.errors_by_kind,
+            "warnings_by_kind": summary.warnings_by_kind,
+            "first_error": summary.first_error.map(first_error_json),
+            "first_desync": summary.first_desync.map(|(r1, r2)| json!({ "r1_line": r1, "r2_line": r2 })),
+            "stopped_early": summary.r1.stopped_early,
+            "unreported_errors": summary.unreported_errors,
+            "r1": summary_fields(&summary.r1, options),
+            "r2": summary_fields(&summary.r2, options),
+        });
+        write_json(path, &report)?;
+    }
+
+    if let Some(path) = &args.validation.multiqc {
+        let mut rows = Map::new();
+        for (input, file_summary) in [(&args.r1, &summary.r1), (&args.r2, &summary.r2)] {
+            let mut row = multiqc_row(file_summary, verdict);
+            row["pair_errors"] = json!(summary.pair_errors);
+            rows.insert(sample_name(input), row);
+        }
+        write_json(path, &multiqc_content(rows))?;
+    }
+    Ok(())
+}
+
+fn run_check(args: &CheckArgs) -> Result<ExitCode, FastqError> {
+    let input = &args.input;
+    let output = args.output.as_deref();
+    let options = CheckOptions {
+        interleaved: args.interleaved,
+        check_index_length: args.check_index_length,
+        source_name: Some(input_name(input)),
+        ..args.validation.options(output)
+    };
+
+    if is_stdio(input) && matches!(options.duplicates, DuplicateCheck::Bloom { .. }) {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            &tr!(
+                "--duplicates bloom 需要再读一遍输入，不能用于标准输入",
+                "--duplicates bloom reads the input twice and cannot be used with stdin"
+            ),
+        );
+    }
+
+    let (mut log, error_writer) = open_outputs(output)?;
+
+    let result = if is_stdio(input) {
+        writeln!(log, "{}", tr!("正在检查 FASTQ 文件: 标准输入", "Checking FASTQ file: stdin"))?;
+        check_fastq_file(io::stdin(), error_writer, &options)
+    } else {
+        writeln!(log, "{}", tr!("正在检查 FASTQ 文件: {}", "Checking FASTQ file: {}", input.display()))?;
+        check_fastq_reopenable(|| File::open(input), error_writer, &options)
+    };
+
+    let summary = result?;
+    writeln!(log, "{}", tr!("检查完成！", "Check complete!"))?;
+    write_summary(&mut log, &summary, &options)?;
+    write_error_count(&mut log, summary.errors, summary.warnings, output)?;
+    write_truncation(&mut log, summary.stopped_early, summary.unreported_errors, &options)?;
+
+    // 警告只在用 --fail-on 选中时导致失败
+    let failing = failing_kinds(&summary.errors_by_kind, &summary.warnings_by_kind, &args.validation, &options);
+    write_check_reports(args, &options, &summary, verdict(&failing))?;
+    Ok(exit_status(&failing))
+}
+
+fn run_check_pair(args: &PairArgs) -> Result<ExitCode, FastqError> {
+    let output = args.output.as_deref();
+    let options = CheckOptions {
+        pair_source_names: Some((input_name(&args.r1), input_name(&args.r2))),
+        ..args.validation.options(output)
+    };
+
+    if matches!(options.duplicates, DuplicateCheck::Bloom { .. }) {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            &tr!(
+                "check-pair 不支持 --duplicates bloom，请使用 exact",
+                "check-pair does not support --duplicates bloom, use exact"
+            ),
+        );
+    }
+    if is_stdio(&args.r1) && is_stdio(&args.r2) {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            &tr!("R1 与 R2 不能都从标准输入读取", "R1 and R2 cannot both be read from stdin"),
+        );
+    }
+
+    let (mut log, error_writer) = open_outputs(output)?;
+    writeln!(
+        log,
+        "{}",
+        tr!(
+            "正在检查双端 FASTQ 文件: {} / {}",
+            "Checking paired FASTQ files: {} / {}",
+            input_name(&args.r1),
+            input_name(&args.r2)
+        )
+    )?;
+
+    let result = match (open_path(&args.r1), open_path(&args.r2)) {
+        (Ok(r1), Ok(r2)) => check_fastq_pair(r1, r2, error_writer, &options),
+        (Err(e), _) | (_, Err(e)) => Err(e.into()),
+    };
+
+    let summary = result?;
+    writeln!(log, "{}", tr!("检查完成！", "Check complete!"))?;
+    writeln!(log, "[R1]")?;
+    write_summary(&mut log, &summary.r1, &options)?;
+    writeln!(log, "[R2]")?;
+    write_summary(&mut log, &summary.r2, &options)?;
+
+    // 提前停止时两个文件都没有读完，记录数不作比较
+    let count_mismatch = !summary.r1.stopped_early
+        && summary.r1.records != summary.r2.records
+        && options.severity_of("pair_count_mismatch") != Severity::Off;
+    if let Some((line1, line2)) = summary.first_desync {
+        writeln!(
+            log,
+            "{}",
+            tr!(
+                "R1 与 R2 从 R1 行 {} / R2 行 {} 开始不同步。",
+                "R1 and R2 go out of sync at R1 line {} / R2 line {}.",
+                line1,
+                line2
+            )
+        )?;
+    }
+    if count_mismatch {
+        writeln!(
+            log,
+            "{}",
+            tr!(
+                "R1 与 R2 的记录数不同: {} / {}",
+                "R1 and R2 have different record counts: {} / {}",
+                summary.r1.records,
+                summary.r2.records
+            )
+        )?;
+    }
+    write_error_count(&mut log, summary.errors(), summary.warnings(), output)?;
+    write_truncation(&mut log, summary.r1.stopped_early, summary.unreported_errors, &options)?;
+
+    let failing = failing_kinds(&summary.errors_by_kind, &summary.warnings_by_kind, &args.validation, &options);
+    write_pair_reports(args, &options, &summary, verdict(&failing))?;
+    Ok(exit_status(&failing))
+}
+
+/// 在解析参数前取出 `--lang` 的值
+fn lang_from_args() -> Option<Lang> {
+    let args: Vec<String> = env::args().collect();
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--lang") {
+        Some("") => args.get(i + 1)?.parse().ok(),
+        Some(value) => value.strip_prefix('=')?.parse().ok(),
+        None => None,
+    })
+}
+
+fn main() -> ExitCode {
+    // 参数本身的错误信息也按 --lang 或环境变量的语言输出
+    check_fastq::set_lang(lang_from_args().unwrap_or_else(Lang::from_env));
+    let cli = match command().try_get_matches().and_then(|matches| Cli::from_arg_matches(&matches)) {
+        Ok(cli) => cli,
+        // --help、--version 也以错误的形式返回，但不是用法错误
+        Err(e) if !e.use_stderr() => e.exit(),
+        Err(e) => {
+            let _ = e.print();
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    if let Some(lang) = cli.lang {
+        check_fastq::set_lang(lang);
+    }
+
+    let result = match &cli.command {
+        Commands::Check(args) => run_check(args),
+        Commands::CheckPair(args) => run_check_pair(args),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", tr!("处理文件时出错: {}", "Error while processing: {}", e));
+        ExitCode::from(EXIT_IO)
+    })
+}
@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufWriter, Read, Write};
+use std::time::Instant;
+
+use crate::check::{advance, RecordChecker};
+use crate::i18n::tr;
+use crate::report::ErrorReporter;
+use crate::{
+    CheckOptions, DuplicateCheck, ErrorLocation, FastqError, FastqReader, FastqRecordRef, Severity, ValidationSummary,
+};
+
+/// 成对检查的统计结果
+#[derive(Debug, Clone, Default)]
+pub struct PairSummary {
+    /// R1 文件的检查结果
+    pub r1: ValidationSummary,
+    /// R2 文件的检查结果
+    pub r2: ValidationSummary,
+    /// 严重程度为错误的配对问题数（读段名不一致、记录数不同）
+    pub pair_errors: usize,
+    /// 严重程度为警告的配对问题数
+    pub pair_warnings: usize,
+    /// 两个文件与配对检查合计的各类错误数，键为 `FastqError::kind`
+    pub errors_by_kind: BTreeMap<&'static str, usize>,
+    /// 两个文件与配对检查合计的各类警告数，键为 `FastqError::kind`
+    pub warnings_by_kind: BTreeMap<&'static str, usize>,
+    /// 配对检查发现的第一个错误（行号为 R1 的行号），两个文件各自的见 `r1.first_error`、`r2.first_error`
+    pub first_error: Option<ErrorLocation>,
+    /// 第一对读段名不一致的记录的标题行行号 (R1, R2)，`pair_name_mismatch` 关闭时为 `None`
+    pub first_desync: Option<(usize, usize)>,
+    /// 超过 `CheckOptions::max_errors_per_kind` 而没有写入错误输出的错误数（两个文件合计）
+    pub unreported_errors: usize,
+}
+
+impl PairSummary {
+    /// 两个文件的错误与配对错误之和
+    pub fn errors(&self) -> usize {
+        self.r1.errors + self.r2.errors + self.pair_errors
+    }
+
+    /// 两个文件的警告与配对警告之和
+    pub fn warnings(&self) -> usize {
+        self.r1.warnings + self.r2.warnings + self.pair_warnings
+    }
+}
+
+/// 去掉读段名末尾的 `/1`、`/2` 配对后缀
+///
+/// Casava 1.8+ 的注释字段在空白之后，`FastqRecordRef::read_id` 已经将其去掉。
+pub fn mate_name(read_id: &[u8]) -> &[u8] {
+    match read_id {
+        [name @ .., b'/', b'1' | b'2'] => name,
+        _ => read_id,
+    }
+}
+
+/// 读段序号（1 或 2）：取自读段名的 `/1`、`/2` 后缀，或 Casava 1.8+ 注释的第一个字段
+pub fn read_number(header: &[u8]) -> Option<u8> {
+    let header = header.strip_prefix(b"@").unwrap_or(header);
+    let (id, comment) = match header.iter().position(|b| b.is_ascii_whitespace()) {
+        Some(pos) => (&header[..pos], header[pos..].trim_ascii_start()),
+        None => (header, &b""[..]),
+    };
+
+    match (id, comment) {
+        ([.., b'/', n @ (b'1' | b'2')], _) => Some(n - b'0'),
+        (_, [n @ (b'1' | b'2'), b':', ..]) => Some(n - b'0'),
+        _ => None,
+    }
+}
+
+/// 交错格式中尚未配对的读段
+struct PendingMate {
+    id: Vec<u8>,
+    number: Option<u8>,
+    line: usize,
+    offset: u64,
+    raw: Vec<u8>, // 原始的 4 行，用于写入错误输出
+}
+
+/// 交错格式（R1、R2 记录交替出现）的配对检查
+///
+/// 相邻的两条记录读段名一致时视为一对，并检查读段序号依次为 1、2；
+/// 不一致时前一条是孤立读段，后一条作为下一对的开头，这样丢失一端后不会影响后面的配对。
+pub(crate) struct MateTracker {
+    pending: PendingMate,
+    has_pending: bool,
+}
+
+impl MateTracker {
+    pub(crate) fn new() -> Self {
+        MateTracker {
+            pending: PendingMate {
+                id: Vec::new(),
+                number: None,
+                line: 0,
+                offset: 0,
+                raw: Vec::new(),
+            },
+            has_pending: false,
+        }
+    }
+
+    /// 处理一条记录，发现的错误计入 `checker` 并写入 `error_file`
+    pub(crate) fn observe<W: Write>(
+        &mut self,
+        record: &FastqRecordRef<'_>,
+        line_num: usize,
+        offset: u64,
+        checker: &mut RecordChecker,
+        error_file: &mut Option<ErrorReporter<W>>,
+    ) -> io::Result<()> {
+        if self.has_pending {
+            if mate_name(&self.pending.id) == mate_name(record.read_id()) {
+                // 配对完整，检查读段序号的顺序
+                if let Some(found) = self.pending.number
+                    && found != 1
+                {
+                    let err = mate_order_error(&self.pending.id, self.pending.line, 1, found);
+                    if checker.count_error(&err)
+                        && let Some(file) = error_file
+                    {
+                        file.write_error(None, &err, Some(self.pending.offset), self.pending.lines())?;
+                    }
+                }
+                if let Some(found) = read_number(record.header)
+                    && found != 2
+                {
+                    let err = mate_order_error(record.read_id(), line_num, 2, found);
+                    if checker.count_error(&err)
+                        && let Some(file) = error_file
+                    {
+                        file.write_error(None, &err, Some(offset), record.lines())?;
+                    }
+                }
+                self.has_pending = false;
+                return Ok(());
+            }
+
+            // 名称不一致：之前的读段没有配对
+            self.report_orphan(checker, error_file)?;
+        }
+
+        self.pending.id.clear();
+        self.pending.id.extend_from_slice(record.read_id());
+        self.pending.number = read_number(record.header);
+        self.pending.line = line_num;
+        self.pending.offset = offset;
+        self.pending.raw.clear();
+        record.write_to(&mut self.pending.raw)?;
+        self.has_pending = true;
+
+        Ok(())
+    }
+
+    /// 输入结束时，剩下未配对的读段也是孤立读段
+    pub(crate) fn finish<W: Write>(
+        &mut self,
+        checker: &mut RecordChecker,
+        error_file: &mut Option<ErrorReporter<W>>,
+    ) -> io::Result<()> {
+        if !self.has_pending {
+            return Ok(());
+        }
+        self.has_pending = false;
+        self.report_orphan(checker, error_file)
+    }
+
+    fn report_orphan<W: Write>(
+        &self,
+        checker: &mut RecordChecker,
+        error_file: &mut Option<ErrorReporter<W>>,
+    ) -> io::Result<()> {
+        let err = FastqError::OrphanRead {
+            name: String::from_utf8_lossy(&self.pending.id).into_owned(),
+            line: self.pending.line,
+        };
+        if checker.count_error(&err)
+            && let Some(file) = error_file
+        {
+            file.write_error(None, &err, Some(self.pending.offset), self.pending.lines())?;
+        }
+        Ok(())
+    }
+}
+
+fn mate_order_error(id: &[u8], line: usize, expected: u8, found: u8) -> FastqError {
+    FastqError::MateOrder {
+        name: String::from_utf8_lossy(id).into_owned(),
+        line,
+        expected,
+        found,
+    }
+}
+
+impl PendingMate {
+    fn lines(&self) -> impl Iterator<Item = &[u8]> {
+        self.raw.split(|&b| b == b'\n').take(4)
+    }
+}
+
+/// 同步读取 R1、R2 两个 FASTQ 输入，验证各自的记录并检查配对关系
+///
+/// 每对记录的读段名在去掉 `/1`、`/2` 后缀和注释字段后必须相同。只报告第一处不同步的位置，
+/// 之后的记录仍会各自验证和计数；两个文件的记录数不同时另报告一个错误。
+/// 两个输入都只读一遍，因此不支持 Bloom 模式的重复检测。
+pub fn check_fastq_pair<R1, R2, W>(
+    r1: R1,
+    r2: R2,
+    error_output: Option<W>,
+    options: &CheckOptions,
+) -> Result<PairSummary, FastqError>
+where
+    R1: Read + Send,
+    R2: Read + Send,
+    W: Write,
+{
+    if matches!(options.duplicates, DuplicateCheck::Bloom { .. }) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            tr!(
+                "成对检查不支持 Bloom 模式的重复检测",
+                "paired checking does not support Bloom duplicate checking",
+            ),
+        )
+        .into());
+    }
+
+    let start = Instant::now();
+    let mut error_file = match error_output {
+        Some(out) => Some(ErrorReporter::new(BufWriter::new(out), options)?),
+        None => None,
+    };
+    let mut reader1 = FastqReader::from_read(r1)?.with_resync(options.resync);
+    let mut reader2 = FastqReader::from_read(r2)?.with_resync(options.resync);
+    let mut checker1 = RecordChecker::new(options);
+    let mut checker2 = RecordChecker::new(options);
+
+    // 配对检查的统计，只用到其中的错误与警告计数
+    let mut pairing = ValidationSummary::default();
+    let check_names = options.severity_of("pair_name_mismatch") != Severity::Off;
+    let mut first_desync = None;
+    let mut stopped_early = false;
+
+    loop {
+        let errors = checker1.errors() + checker2.errors() + pairing.errors;
+        if options.error_limit_reached(errors) {
+            stopped_early = true;
+            break;
+        }
+
+        let more1 = advance(&mut reader1, &mut checker1, &mut error_file, Some("R1"))?;
+        let more2 = advance(&mut reader2, &mut checker2, &mut error_file, Some("R2"))?;
+        if !more1 || !more2 {
+            // 记录数不同时，继续读完较长的文件以统计记录数
+            // 提前停止时两个文件都没有读完，记录数不再比较
+            if more1 {
+                check_current(&reader1, &mut checker1, &mut error_file, "R1")?;
+                let other = checker2.errors() + pairing.errors;
+                stopped_early = check_rest(&mut reader1, &mut checker1, &mut error_file, "R1", other)?;
+            }
+            if more2 {
+                check_current(&reader2, &mut checker2, &mut error_file, "R2")?;
+                let other = checker1.errors() + pairing.errors;
+                stopped_early = check_rest(&mut reader2, &mut checker2, &mut error_file, "R2", other)?;
+            }
+            break;
+        }
+
+        check_current(&reader1, &mut checker1, &mut error_file, "R1")?;
+        check_current(&reader2, &mut checker2, &mut error_file, "R2")?;
+
+        let (rec1, rec2) = (reader1.record(), reader2.record());
+        if check_names && first_desync.is_none() && mate_name(rec1.read_id()) != mate_name(rec2.read_id()) {
+            let (line1, line2) = (reader1.line_num(), reader2.line_num());
+            first_desync = Some((line1, line2));
+
+            let err = FastqError::PairNameMismatch {
+                r1_line: line1,
+                r2_line: line2,
+                r1_name: String::from_utf8_lossy(rec1.read_id()).into_owned(),
+                r2_name: String::from_utf8_lossy(rec2.read_id()).into_owned(),
+            };
+            if count_pair_error(&mut pairing, &err, options)
+                && let Some(file) = &mut error_file
+            {
+                let lines = rec1.lines().into_iter().chain(rec2.lines());
+                file.write_error(Some("R1/R2"), &err, Some(reader1.byte_offset()), lines)?;
+            }
+        }
+    }
+
+    let (mut r1, _, _) = checker1.finish();
+    let (mut r2, _, _) = checker2.finish();
+    r1.bytes_read = reader1.bytes_read();
+    r2.bytes_read = reader2.bytes_read();
+    r1.stopped_early = stopped_early;
+    r2.stopped_early = stopped_early;
+
+    if !stopped_early && r1.records != r2.records {
+        let err = FastqError::PairCountMismatch {
+            r1_records: r1.records,
+            r2_records: r2.records,
+        };
+        if count_pair_error(&mut pairing, &err, options)
+            && let Some(file) = &mut error_file
+        {
+            file.write_error(Some("R1/R2"), &err, None, [])?;
+        }
+    }
+
+    let mut unreported_errors = 0;
+    if let Some(ref mut file) = error_file {
+        unreported_errors = file.unreported();
+        file.flush()?;
+    }
+    r1.elapsed = start.elapsed();
+    r2.elapsed = r1.elapsed;
+
+    let merge = |maps: [&BTreeMap<&'static str, usize>; 3]| {
+        let mut total = BTreeMap::new();
+        for (&kind, &count) in maps.into_iter().flatten() {
+            *total.entry(kind).or_default() += count;
+        }
+        total
+    };
+    Ok(PairSummary {
+        pair_errors: pairing.errors,
+        pair_warnings: pairing.warnings,
+        errors_by_kind: merge([&r1.errors_by_kind, &r2.errors_by_kind, &pairing.errors_by_kind]),
+        warnings_by_kind: merge([&r1.warnings_by_kind, &r2.warnings_by_kind, &pairing.warnings_by_kind]),
+        first_error: pairing.first_error,
+        r1,
+        r2,
+        first_desync,
+        unreported_errors,
+    })
+}
+
+/// 按严重程度统计配对检查发现的问题，返回是否应写入错误输出（`Severity::Off` 时不写）
+fn count_pair_error(pairing: &mut ValidationSummary, err: &FastqError, options: &CheckOptions) -> bool {
+    let severity = options.severity_of(err.kind());
+    pairing.count(err, severity);
+    severity != Severity::Off
+}
+
+/// 验证读取器当前的记录，出错时写入错误输出
+fn check_current<B: BufRead, W: Write>(
+    reader: &FastqReader<B>,
+    checker: &mut RecordChecker,
+    error_file: &mut Option<ErrorReporter<W>>,
+    label: &str,
+) -> io::Result<()> {
+    let record = reader.record();
+    let mut violations = checker.check(&record, reader.line_num());
+    violations.extend(checker.check_line_ending(reader.crlf_line()));
+    if !violations.is_empty()
+        && let Some(file) = error_file
+    {
+        file.write_errors(Some(label), &violations, Some(reader.byte_offset()), record.lines())?;
+    }
+    Ok(())
+}
+
+/// 验证读取器中剩余的所有记录，`other_errors` 为另一个文件的错误与配对错误数
+///
+/// 错误总数达到 `max_errors` 时停止并返回 `true`。
+fn check_rest<B: BufRead, W: Write>(
+    reader: &mut FastqReader<B>,
+    checker: &mut RecordChecker,
+    error_file: &mut Option<ErrorReporter<W>>,
+    label: &str,
+    other_errors: usize,
+) -> Result<bool, FastqError> {
+    loop {
+        if checker.error_limit_reached(other_errors) {
+            return Ok(true);
+        }
+        if !advance(reader, checker, error_file, Some(label))? {
+            return Ok(false);
+        }
+        check_current(reader, checker, error_file, label)?;
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use toml::{Table, Value};
+
+use crate::i18n::tr;
+use crate::{Alphabet, CheckOptions, DuplicateCheck, Platform, QualityEncoding, Validator};
+
+/// 错误类别的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Severity {
+    /// 不检查、不报告
+    Off,
+    /// 仅作提示
+    Info,
+    /// 报告但不使检查失败
+    Warning,
+    /// 使检查失败
+    #[default]
+    Error,
+}
+
+impl Severity {
+    /// 未配置时各类问题的严重程度：小写碱基、Windows 换行符和带标识符的加号行只是警告，其余为错误
+    pub fn default_for(kind: &str) -> Severity {
+        match kind {
+            "lowercase_base" | "crlf_line_ending" | "plus_line_not_bare" => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(Severity::Off),
+            "info" => Ok(Severity::Info),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(tr!(
+                "未知的严重程度: {}（可选 error、warning、info、off）",
+                "unknown severity: {} (expected error, warning, info, off)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 内置的 `strict` 配置：只允许 ACGTN、区分大小写、加号行只有 +、按识别出的平台检查标题行、不允许空读段，
+/// Windows 换行符也是错误
+const STRICT: &str = r#"
+alphabet = "strict"
+case_sensitive = true
+require_bare_plus = true
+platform = "auto"
+min_length = 1
+
+[rules]
+crlf_line_ending = "error"
+"#;
+
+/// 内置的 `lenient` 配置：不检查碱基，加号行与质量值的问题只作为警告
+const LENIENT: &str = r#"
+alphabet = "any"
+
+[rules]
+plus_line_mismatch = "warning"
+plus_line_not_bare = "off"
+invalid_quality = "warning"
+index_length_mismatch = "warning"
+filtered_read = "info"
+"#;
+
+/// 验证配置：要覆盖的检查选项与各类错误的严重程度，未设置的项保持原值
+///
+/// 可以从 TOML 读取，`profile` 项指定在哪个内置配置的基础上修改：
+///
+/// ```toml
+/// profile = "strict"
+/// alphabet = "iupac"
+/// quality_encoding = "phred33"
+/// min_length = 50
+/// max_errors = 1000
+///
+/// [rules]
+/// plus_line_mismatch = "warning"
+/// duplicate_read_id = "off"
+/// read_length = "warning"
+/// ```
+///
+/// `[rules]` 的键可以是错误类别、错误代码或规则名称（对应该规则报告的所有类别）。
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub alphabet: Option<Alphabet>,
+    pub case_sensitive: Option<bool>,
+    pub require_bare_plus: Option<bool>,
+    pub quality_encoding: Option<QualityEncoding>,
+    pub platform: Option<Platform>,
+    pub duplicates: Option<DuplicateCheck>,
+    pub flag_filtered: Option<bool>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub max_errors: Option<usize>,
+    pub max_errors_per_kind: Option<usize>,
+    /// 各类错误（键为 `FastqError::kind`，自定义规则为规则名称）的严重程度
+    pub severity: BTreeMap<&'static str, Severity>,
+}
+
+impl Profile {
+    /// 内置配置的名称
+    pub const BUILTIN: &'static [&'static str] = &["default", "strict", "lenient"];
+
+    /// 按名称取内置配置，`default` 不修改任何选项
+    pub fn builtin(name: &str) -> Option<Profile> {
+        let text = match name.to_ascii_lowercase().as_str() {
+            "default" => "",
+            "strict" => STRICT,
+            "lenient" => LENIENT,
+            _ => return None,
+        };
+        Some(Profile::from_toml(text).expect("内置配置有效"))
+    }
+
+    /// 解析 TOML 格式的配置，`[rules]` 中可以使用内置规则的名称
+    pub fn from_toml(text: &str) -> Result<Profile, String> {
+        Profile::from_toml_with(text, &Validator::new())
+    }
+
+    /// 解析 TOML 格式的配置，`[rules]` 中可以使用 `validator` 中各条规则（包括自定义规则）的名称
+    ///
+    /// 规则名称对应该规则报告的所有错误类别，见 `Validator::resolve_kinds`。
+    pub fn from_toml_with(text: &str, validator: &Validator) -> Result<Profile, String> {
+        let table: Table = text
+            .parse()
+            .map_err(|e| tr!("无法解析配置: {}", "invalid configuration: {}", e))?;
+
+        let mut profile = match table.get("profile") {
+            Some(value) => {
+                let name = string("profile", value)?;
+                Profile::builtin(name).ok_or_else(|| unknown_profile(name))?
+            }
+            None => Profile::default(),
+        };
+        let mut bloom_memory = None;
+
+        for (key, value) in &table {
+            match key.as_str() {
+                "profile" => {}
+                "alphabet" => profile.alphabet = Some(parse(key, value)?),
+                "case_sensitive" => profile.case_sensitive = Some(boolean(key, value)?),
+                "require_bare_plus" => profile.require_bare_plus = Some(boolean(key, value)?),
+                "quality_encoding" => profile.quality_encoding = Some(parse(key, value)?),
+                "platform" => profile.platform = Some(parse(key, value)?),
+                "duplicates" => profile.duplicates = Some(parse(key, value)?),
+                "bloom_memory" => bloom_memory = Some(count(key, value)?),
+                "flag_filtered" => profile.flag_filtered = Some(boolean(key, value)?),
+                "min_length" => profile.min_length = Some(count(key, value)?),
+                "max_length" => profile.max_length = Some(count(key, value)?),
+                "max_errors" => profile.max_errors = Some(positive(key, value)?),
+                "max_errors_per_kind" => profile.max_errors_per_kind = Some(count(key, value)?),
+                "rules" => {
+                    let Value::Table(rules) = value else {
+                        return Err(wrong_type(key, "table"));
+                    };
+                    for (name, value) in rules {
+                        let kinds = validator.resolve_kinds(name).ok_or_else(|| {
+                            tr!(
+                                "配置中未知的错误类别或规则: {}",
+                                "unknown error kind or rule in configuration: {}",
+                                name
+                            )
+                        })?;
+                        let severity = parse(name, value)?;
+                        for kind in kinds {
+                            profile.severity.insert(kind, severity);
+                        }
+                    }
+                }
+                _ => {
+                    return Err(tr!("未知的配置项: {}", "unknown configuration key: {}", key));
+                }
+            }
+        }
+
+        // bloom_memory 以 MiB 为单位，只对 bloom 模式有效
+        if let (Some(DuplicateCheck::Bloom { memory }), Some(mib)) = (&mut profile.duplicates, bloom_memory) {
+            *memory = mib * 1024 * 1024;
+        }
+        Ok(profile)
+    }
+
+    /// 用配置中设置的项覆盖 `options`
+    pub fn apply(&self, options: &mut CheckOptions) {
+        fn set<T: Copy>(target: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *target = value;
+            }
+        }
+
+        set(&mut options.alphabet, self.alphabet);
+        set(&mut options.case_sensitive, self.case_sensitive);
+        set(&mut options.require_bare_plus, self.require_bare_plus);
+        set(&mut options.duplicates, self.duplicates);
+        set(&mut options.flag_filtered, self.flag_filtered);
+        if self.quality_encoding.is_some() {
+            options.quality_encoding = self.quality_encoding;
+        }
+        if self.platform.is_some() {
+            options.platform = self.platform;
+        }
+        if self.min_length.is_some() {
+            options.min_length = self.min_length;
+        }
+        if self.max_length.is_some() {
+            options.max_length = self.max_length;
+        }
+        if self.max_errors.is_some() {
+            options.max_errors = self.max_errors;
+        }
+        if self.max_errors_per_kind.is_some() {
+            options.max_errors_per_kind = self.max_errors_per_kind;
+        }
+        options.severity.extend(&self.severity);
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    /// 按名称取内置配置
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Profile::builtin(s).ok_or_else(|| unknown_profile(s))
+    }
+}
+
+fn unknown_profile(name: &str) -> String {
+    tr!(
+        "未知的配置: {}（可选 {}）",
+        "unknown profile: {} (expected {})",
+        name,
+        Profile::BUILTIN.join(", ")
+    )
+}
+
+fn wrong_type(key: &str, expected: &str) -> String {
+    tr!("配置项 {} 应为 {}", "configuration key {} must be a {}", key, expected)
+}
+
+fn string<'a>(key: &str, value: &'a Value) -> Result<&'a str, String> {
+    value.as_str().ok_or_else(|| wrong_type(key, "string"))
+}
+
+fn boolean(key: &str, value: &Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| wrong_type(key, "boolean"))
+}
+
+/// 非负整数
+fn count(key: &str, value: &Value) -> Result<usize, String> {
+    value
+        .as_integer()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| wrong_type(key, "non-negative integer"))
+}
+
+/// 正整数
+fn positive(key: &str, value: &Value) -> Result<usize, String> {
+    match count(key, value) {
+        Ok(0) | Err(_) => Err(wrong_type(key, "positive integer")),
+        Ok(n) => Ok(n),
+    }
+}
+
+/// 按取值类型的 `FromStr` 解析字符串
+fn parse<T: FromStr<Err = String>>(key: &str, value: &Value) -> Result<T, String> {
+    string(key, value)?.parse()
+}
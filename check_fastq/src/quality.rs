@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::i18n::tr;
+
+/// 可打印质量值字符的范围，任何编码都不会超出
+const PRINTABLE: std::ops::RangeInclusive<u8> = b'!'..=b'~';
+
+/// 质量值编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityEncoding {
+    /// Sanger / Illumina 1.8+，偏移 33
+    Phred33,
+    /// Illumina 1.3-1.7，偏移 64
+    Phred64,
+    /// Solexa / Illumina 1.0，偏移 64，允许负值（最低为 -5）
+    Solexa64,
+}
+
+impl QualityEncoding {
+    /// 该编码允许的最小字符
+    pub fn min_byte(self) -> u8 {
+        match self {
+            QualityEncoding::Phred33 => b'!',
+            QualityEncoding::Phred64 => b'@',
+            QualityEncoding::Solexa64 => b';',
+        }
+    }
+
+    /// 该编码允许的最大字符
+    pub fn max_byte(self) -> u8 {
+        b'~'
+    }
+
+    /// 质量值中第一个超出该编码范围的字符的位置（从 0 开始）
+    pub fn first_invalid(self, quality: &[u8]) -> Option<usize> {
+        let (min, max) = (self.min_byte(), self.max_byte());
+        quality.iter().position(|&q| q < min || q > max)
+    }
+}
+
+impl FromStr for QualityEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "phred33" | "phred+33" | "sanger" => Ok(QualityEncoding::Phred33),
+            "phred64" | "phred+64" => Ok(QualityEncoding::Phred64),
+            "solexa64" | "solexa+64" | "solexa" => Ok(QualityEncoding::Solexa64),
+            _ => Err(tr!(
+                "未知的质量值编码: {}（可选 phred33、phred64、solexa64）",
+                "unknown quality encoding: {} (expected phred33, phred64, solexa64)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for QualityEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            QualityEncoding::Phred33 => "Phred+33",
+            QualityEncoding::Phred64 => "Phred+64",
+            QualityEncoding::Solexa64 => "Solexa+64",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 根据观察到的质量值字符推断编码
+#[derive(Debug, Clone)]
+pub struct QualityDetector {
+    min: u8,
+    max: u8,
+    records: usize,
+}
+
+impl Default for QualityDetector {
+    fn default() -> Self {
+        QualityDetector {
+            min: u8::MAX,
+            max: 0,
+            records: 0,
+        }
+    }
+}
+
+impl QualityDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一条质量值，不可打印字符不参与推断
+    pub fn observe(&mut self, quality: &[u8]) {
+        for &q in quality.iter().filter(|q| PRINTABLE.contains(q)) {
+            self.min = self.min.min(q);
+            self.max = self.max.max(q);
+        }
+        self.records += 1;
+    }
+
+    /// 已观察的记录数
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// 推断的编码，尚未观察到任何质量值时返回 `None`
+    ///
+    /// 出现低于 `;` 的字符只可能是 Phred+33；高于 `j`（Phred+64 的 Q42，旧仪器达不到）
+    /// 也视为 Phred+33，如 PacBio HiFi 的高质量值；否则低于 `@` 只可能是 Solexa+64。
+    /// 所有字符都不低于 `@` 时，若出现高于 `K` 的字符判断为 Phred+64，否则仍视为 Phred+33
+    /// （高质量的现代数据也可能全部落在这个区间）。
+    pub fn guess(&self) -> Option<QualityEncoding> {
+        if self.min > self.max {
+            return None;
+        }
+        let encoding = if self.min < b';' || self.max > b'j' {
+            QualityEncoding::Phred33
+        } else if self.min < b'@' {
+            QualityEncoding::Solexa64
+        } else if self.max > b'K' {
+            QualityEncoding::Phred64
+        } else {
+            QualityEncoding::Phred33
+        };
+        Some(encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(qualities: &[&[u8]]) -> Option<QualityEncoding> {
+        let mut detector = QualityDetector::new();
+        for quality in qualities {
+            detector.observe(quality);
+        }
+        detector.guess()
+    }
+
+    #[test]
+    fn guesses_encoding_from_the_observed_range() {
+        assert_eq!(guess(&[]), None);
+        assert_eq!(guess(&[b"!#5II"]), Some(QualityEncoding::Phred33));
+        assert_eq!(guess(&[b"@Ihh", b"BBBB"]), Some(QualityEncoding::Phred64));
+        assert_eq!(guess(&[b";@hh"]), Some(QualityEncoding::Solexa64));
+        // 全部高于 @ 但不超过 K，无法区分时视为 Phred+33
+        assert_eq!(guess(&[b"@FFK"]), Some(QualityEncoding::Phred33));
+    }
+
+    #[test]
+    fn high_quality_phred33_is_not_mistaken_for_phred64() {
+        assert_eq!(guess(&[b"FFF~"]), Some(QualityEncoding::Phred33));
+        assert_eq!(guess(&[b"@Ihk"]), Some(QualityEncoding::Phred33));
+    }
+
+    #[test]
+    fn checks_range_of_each_encoding() {
+        assert_eq!(QualityEncoding::Phred33.first_invalid(b"!I~"), None);
+        assert_eq!(QualityEncoding::Phred33.first_invalid(b"II I"), Some(2));
+        assert_eq!(QualityEncoding::Phred64.first_invalid(b"@h5h"), Some(2));
+        assert_eq!(QualityEncoding::Solexa64.first_invalid(b";@h"), None);
+    }
+}
@@ -0,0 +1,300 @@
+use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::compression::{decompress, open_input};
+use crate::{FastqError, FastqRecord, FastqRecordRef};
+
+/// 错位恢复时保留下来用于报告的被跳过行数，更多的行只计入 `SkippedLines` 的行号范围
+const KEPT_SKIPPED_LINES: usize = 8;
+
+/// 流式 FASTQ 读取器，逐条产出记录，同时跟踪行号与字节偏移
+///
+/// 读取按字节进行，不要求输入是合法的 UTF-8。`read_next` 与 `record` 提供借用内部缓冲区的
+/// `FastqRecordRef`，缓冲区在记录之间复用；`Iterator` 实现则产出拥有所有权的 `FastqRecord`。
+pub struct FastqReader<R: BufRead> {
+    reader: R,
+    buf: Vec<u8>,                // 已读入但尚未丢弃的原始字节，跨记录复用
+    lines: Vec<Range<usize>>,    // buf 中各行的范围（不含换行符）
+    current: usize,              // lines 开头属于当前记录的行数
+    buf_line: usize,             // lines[0] 的行号（从 1 开始）
+    buf_offset: u64,             // buf[0] 在输入中的字节偏移
+    lines_read: usize,           // 已读取的行数
+    bytes_read: u64,             // 已读取的字节数
+    record_line: usize,          // 最近一条记录标题行的行号（从 1 开始）
+    record_offset: u64,          // 最近一条记录在输入中的起始字节偏移
+    crlf_line: Option<usize>,    // 第一个以 \r\n 结尾的行的行号
+    skipped_buf: Vec<u8>,        // 最近一次错位恢复跳过的开头几行
+    skipped: Vec<Range<usize>>,  // skipped_buf 中各行的范围，没有刚跳过的行时为空
+    resync: bool,                // 记录错位后是否向后搜索下一条完整记录
+    finished: bool,              // 遇到文件结尾或致命错误后不再继续读取
+}
+
+impl<R: BufRead> FastqReader<R> {
+    /// 基于任意 `BufRead` 创建读取器
+    pub fn new(reader: R) -> Self {
+        FastqReader {
+            reader,
+            buf: Vec::with_capacity(1024),
+            lines: Vec::with_capacity(4),
+            current: 0,
+            buf_line: 1,
+            buf_offset: 0,
+            lines_read: 0,
+            bytes_read: 0,
+            record_line: 0,
+            record_offset: 0,
+            crlf_line: None,
+            skipped_buf: Vec::new(),
+            skipped: Vec::new(),
+            resync: false,
+            finished: false,
+        }
+    }
+
+    /// 启用或关闭错位恢复
+    ///
+    /// 启用后，若记录的第 3 行不以 `+` 开头（多出或缺少了行），读取器会向后搜索下一组
+    /// `@`/序列/`+`/质量值 四行并从那里继续，被跳过的行以一个 `SkippedLines` 错误报告。
+    pub fn with_resync(mut self, resync: bool) -> Self {
+        self.resync = resync;
+        self
+    }
+
+    /// 最近一次返回的记录的标题行行号
+    pub fn line_num(&self) -> usize {
+        self.record_line
+    }
+
+    /// 最近一次返回的记录的起始字节偏移
+    pub fn byte_offset(&self) -> u64 {
+        self.record_offset
+    }
+
+    /// 目前为止读取的总行数
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    /// 目前为止读取的总字节数
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// 目前为止第一个以 Windows 换行符（`\r\n`）结尾的行的行号
+    ///
+    /// 换行符在读取时已经去掉，不会出现在记录中。
+    pub fn crlf_line(&self) -> Option<usize> {
+        self.crlf_line
+    }
+
+    /// 取回底层读取器
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// 读取下一条记录到内部缓冲区，文件结束时返回 `false`
+    ///
+    /// 读取成功后通过 `record` 获取记录视图。`SkippedLines` 之后可以继续读取，
+    /// 其他错误之后读取器不再继续。
+    pub fn read_next(&mut self) -> Result<bool, FastqError> {
+        if self.finished {
+            return Ok(false);
+        }
+
+        let result = self.fill_record();
+        match result {
+            Ok(true) | Err(FastqError::SkippedLines { .. }) => {}
+            _ => self.finished = true,
+        }
+        result
+    }
+
+    /// 最近一次读取的记录视图，在下一次调用 `read_next` 前有效
+    pub fn record(&self) -> FastqRecordRef<'_> {
+        FastqRecordRef {
+            header: self.line(0),
+            sequence: self.line(1),
+            plus_line: self.line(2),
+            quality: self.line(3),
+        }
+    }
+
+    /// 当前记录实际读到的各行
+    ///
+    /// 文件在记录中途结束时少于 4 行；返回 `SkippedLines` 之后则是被跳过的各行，
+    /// 跳过的行很多时只有开头的几行。
+    pub fn raw_lines(&self) -> impl Iterator<Item = &[u8]> {
+        let (buf, lines) = match self.skipped.is_empty() {
+            true => (&self.buf, &self.lines[..self.current]),
+            false => (&self.skipped_buf, &self.skipped[..]),
+        };
+        lines.iter().map(|range| &buf[range.clone()])
+    }
+
+    /// 缓冲区中的第 `i` 行，不存在时为空
+    fn line(&self, i: usize) -> &[u8] {
+        match self.lines.get(i) {
+            Some(range) => &self.buf[range.clone()],
+            None => &[],
+        }
+    }
+
+    /// 读入一行并追加到缓冲区，文件结束时返回 `false`
+    fn read_line(&mut self) -> Result<bool, FastqError> {
+        let start = self.buf.len();
+        let n = self.reader.read_until(b'\n', &mut self.buf)?;
+        if n == 0 {
+            return Ok(false);
+        }
+
+        self.lines_read += 1;
+        self.bytes_read += n as u64;
+
+        // 去掉行尾换行符
+        let mut end = self.buf.len();
+        if self.buf[end - 1] == b'\n' {
+            end -= 1;
+            if end > start && self.buf[end - 1] == b'\r' {
+                end -= 1;
+                self.crlf_line.get_or_insert(self.lines_read);
+            }
+        }
+        self.lines.push(start..end);
+        Ok(true)
+    }
+
+    /// 读入足够的行使缓冲区至少有 `n` 行，返回实际的行数（文件结束时可能少于 `n`）
+    fn fill_lines(&mut self, n: usize) -> Result<usize, FastqError> {
+        while self.lines.len() < n && self.read_line()? {}
+        Ok(self.lines.len())
+    }
+
+    /// 丢弃缓冲区开头的 `n` 行
+    fn consume(&mut self, n: usize) {
+        if n >= self.lines.len() {
+            self.buf_offset += self.buf.len() as u64;
+            self.buf_line += self.lines.len();
+            self.buf.clear();
+            self.lines.clear();
+            return;
+        }
+
+        // 保留已预读的行（只在错位恢复时出现）
+        let cut = self.lines[n].start;
+        self.buf.drain(..cut);
+        self.lines.drain(..n);
+        for range in &mut self.lines {
+            *range = range.start - cut..range.end - cut;
+        }
+        self.buf_offset += cut as u64;
+        self.buf_line += n;
+    }
+
+    /// 缓冲区从第 `i` 行起的四行是否像一条完整记录
+    fn starts_record(&self, i: usize) -> bool {
+        self.line(i).starts_with(b"@")
+            && self.line(i + 2).starts_with(b"+")
+            && self.line(i + 1).len() == self.line(i + 3).len()
+    }
+
+    /// 将一条完整记录（4行）读入缓冲区，文件结束时返回 `false`
+    ///
+    /// 文件在记录中途结束时返回 `TruncatedRecord`，已读到的行仍可通过 `raw_lines` 获取。
+    fn fill_record(&mut self) -> Result<bool, FastqError> {
+        self.consume(self.current);
+        self.current = 0;
+        self.skipped.clear();
+        self.skipped_buf.clear();
+
+        let available = self.fill_lines(4)?;
+        if available == 0 {
+            return Ok(false);
+        }
+        self.record_line = self.buf_line;
+        self.record_offset = self.buf_offset;
+
+        if available < 4 {
+            self.current = available;
+            return Err(FastqError::TruncatedRecord {
+                line: self.record_line,
+                lines_present: available,
+            });
+        }
+
+        if self.resync && !self.line(2).starts_with(b"+") {
+            return Err(self.skip_to_next_record());
+        }
+
+        self.current = 4;
+        Ok(true)
+    }
+
+    /// 从当前位置向后搜索下一条看起来完整的记录，返回描述被跳过行的错误
+    ///
+    /// 不可能再作为记录开头的行随搜索进度丢弃，只保留开头的 `KEPT_SKIPPED_LINES` 行用于报告，
+    /// 因此输入末尾即使有大量损坏的内容，内存占用也不会增长。
+    fn skip_to_next_record(&mut self) -> FastqError {
+        let first = self.buf_line;
+        loop {
+            self.skip_lines(1);
+            let available = match self.fill_lines(4) {
+                Ok(n) => n,
+                Err(err) => return err,
+            };
+            // 剩余的行已经不足以组成记录，全部跳过
+            if available < 4 {
+                self.skip_lines(available);
+                break;
+            }
+            if self.starts_record(0) {
+                break;
+            }
+        }
+
+        FastqError::SkippedLines {
+            first,
+            last: self.buf_line - 1,
+        }
+    }
+
+    /// 跳过缓冲区开头的 `n` 行，保留的行数未满时先复制下来
+    fn skip_lines(&mut self, n: usize) {
+        for i in 0..n {
+            if self.skipped.len() == KEPT_SKIPPED_LINES {
+                break;
+            }
+            let range = self.lines[i].clone();
+            let start = self.skipped_buf.len();
+            self.skipped_buf.extend_from_slice(&self.buf[range]);
+            self.skipped.push(start..self.skipped_buf.len());
+        }
+        self.consume(n);
+    }
+}
+
+impl<'a> FastqReader<Box<dyn BufRead + Send + 'a>> {
+    /// 打开 FASTQ 文件，自动识别并解压 gzip/BGZF、bzip2、xz 与 zstd
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, FastqError> {
+        let (reader, _) = open_input(path)?;
+        Ok(FastqReader::new(reader))
+    }
+
+    /// 从任意字节流（如标准输入）读取，同样自动识别压缩格式
+    pub fn from_read<R: Read + Send + 'a>(input: R) -> Result<Self, FastqError> {
+        let (reader, _) = decompress(BufReader::new(input))?;
+        Ok(FastqReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Iterator for FastqReader<R> {
+    type Item = Result<FastqRecord, FastqError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_next() {
+            Ok(true) => Some(Ok(self.record().to_record())),
+            Ok(false) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+
+use crate::i18n::{error_message, lang, tr};
+use crate::{header_read_id, CheckOptions, Diagnostic, FastqError, Severity, Violation};
+
+/// 错误输出的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// 可读的文本：按当前语言写出的错误信息、原始记录和 `---` 分隔线
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象
+    Jsonl,
+    /// 制表符分隔，第一行为列名
+    Tsv,
+    /// rustc 风格的诊断信息，用 `^` 指出出错的位置
+    Pretty,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Ok(ReportFormat::Text),
+            "jsonl" | "json-lines" | "ndjson" => Ok(ReportFormat::Jsonl),
+            "tsv" => Ok(ReportFormat::Tsv),
+            "pretty" => Ok(ReportFormat::Pretty),
+            _ => Err(tr!(
+                "未知的报告格式: {}（可选 text、jsonl、tsv、pretty）",
+                "unknown report format: {} (expected text, jsonl, tsv, pretty)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReportFormat::Text => "text",
+            ReportFormat::Jsonl => "jsonl",
+            ReportFormat::Tsv => "tsv",
+            ReportFormat::Pretty => "pretty",
+        };
+        f.write_str(name)
+    }
+}
+
+/// TSV 格式的列名
+const TSV_COLUMNS: &str = "severity\tcode\tkind\tinput\tline\tbyte_offset\tread_name\tmessage";
+
+/// 按选定格式写出错误记录
+pub(crate) struct ErrorReporter<W: Write> {
+    out: W,
+    /// 输出格式、诊断信息中的输入名称、每类错误的上限与各类问题的严重程度
+    options: CheckOptions,
+    /// 各类错误已写出的条数
+    written_by_kind: BTreeMap<&'static str, usize>,
+    /// 超过上限而没有写出的错误数
+    unreported: usize,
+}
+
+impl<W: Write> ErrorReporter<W> {
+    pub(crate) fn new(mut out: W, options: &CheckOptions) -> io::Result<Self> {
+        if options.report_format == ReportFormat::Tsv {
+            writeln!(out, "{}", TSV_COLUMNS)?;
+        }
+        Ok(ErrorReporter {
+            out,
+            options: options.clone(),
+            written_by_kind: BTreeMap::new(),
+            unreported: 0,
+        })
+    }
+
+    /// 写出一条错误及其对应的原始行，严重程度按配置决定
+    ///
+    /// `label` 标明错误来自哪个输入（如成对检查的 R1/R2），`offset` 为记录在输入中的起始字节偏移。
+    pub(crate) fn write_error<'a>(
+        &mut self,
+        label: Option<&str>,
+        err: &FastqError,
+        offset: Option<u64>,
+        lines: impl IntoIterator<Item = &'a [u8]>,
+    ) -> io::Result<()> {
+        let severity = self.options.severity_of(err.kind());
+        self.write_entries(label, vec![(err, severity)], offset, lines)
+    }
+
+    /// 写出同一条记录的多个问题，每个问题按其自身的严重程度标明是错误还是警告
+    pub(crate) fn write_errors<'a>(
+        &mut self,
+        label: Option<&str>,
+        violations: &[Violation],
+        offset: Option<u64>,
+        lines: impl IntoIterator<Item = &'a [u8]>,
+    ) -> io::Result<()> {
+        let entries = violations.iter().map(|v| (&v.error, v.severity)).collect();
+        self.write_entries(label, entries, offset, lines)
+    }
+
+    /// 写出同一条记录的多个（错误，严重程度）
+    ///
+    /// 文本格式先列出所有错误再写一次原始行，其他格式每个错误各占一项；每项都标明是错误还是警告。
+    /// 某类错误已写出 `max_errors_per_kind` 条时只计数，不再写出。
+    fn write_entries<'a>(
+        &mut self,
+        label: Option<&str>,
+        mut entries: Vec<(&FastqError, Severity)>,
+        offset: Option<u64>,
+        lines: impl IntoIterator<Item = &'a [u8]>,
+    ) -> io::Result<()> {
+        entries.retain(|(err, _)| self.admit(err));
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let lines: Vec<&[u8]> = lines.into_iter().collect();
+
+        match self.options.report_format {
+            ReportFormat::Text => {
+                for (err, severity) in entries {
+                    let prefix = match severity {
+                        Severity::Warning => lang().pick("警告", "warning"),
+                        Severity::Info => lang().pick("提示", "info"),
+                        Severity::Error | Severity::Off => lang().pick("错误", "error"),
+                    };
+                    let message = error_message(err);
+                    match label {
+                        Some(label) => writeln!(self.out, "{} [{}] ({}): {}", prefix, err.code(), label, message)?,
+                        None => writeln!(self.out, "{} [{}]: {}", prefix, err.code(), message)?,
+                    }
+                }
+                for line in lines {
+                    self.out.write_all(line)?;
+                    self.out.write_all(b"\n")?;
+                }
+                writeln!(self.out, "---")
+            }
+            ReportFormat::Jsonl => {
+                for (err, severity) in entries {
+                    let entry = json!({
+                        "severity": severity.to_string(),
+                        "code": err.code(),
+                        "kind": err.kind(),
+                        "input": label,
+                        "line": err.line(),
+                        "byte_offset": offset,
+                        "read_name": read_name(&lines),
+                        "message": err.to_string(),
+                        "fields": error_fields(err),
+                        "record": lines.iter().map(|l| String::from_utf8_lossy(l)).collect::<Vec<_>>(),
+                    });
+                    serde_json::to_writer(&mut self.out, &entry)?;
+                    writeln!(self.out)?;
+                }
+                Ok(())
+            }
+            ReportFormat::Pretty => {
+                for (err, severity) in entries {
+                    let mut diagnostic = Diagnostic::new(err, lines.iter().copied())
+                        .color(self.options.color)
+                        .severity(severity);
+                    if let Some(source) = source_name(&self.options, label) {
+                        diagnostic = diagnostic.source(source);
+                    }
+                    writeln!(self.out, "{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            ReportFormat::Tsv => {
+                let optional = |value: Option<String>| value.unwrap_or_default();
+                for (err, severity) in entries {
+                    writeln!(
+                        self.out,
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                        severity,
+                        err.code(),
+                        err.kind(),
+                        label.unwrap_or_default(),
+                        optional(err.line().map(|l| l.to_string())),
+                        optional(offset.map(|o| o.to_string())),
+                        tsv_escape(&optional(read_name(&lines))),
+                        tsv_escape(&err.to_string()),
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 该错误是否写出：同类错误已写出 `max_errors_per_kind` 条时记为未写出
+    fn admit(&mut self, err: &FastqError) -> bool {
+        let written = self.written_by_kind.entry(err.kind()).or_default();
+        if self.options.max_errors_per_kind.is_some_and(|max| *written >= max) {
+            self.unreported += 1;
+            return false;
+        }
+        *written += 1;
+        true
+    }
+
+    /// 超过每类上限而没有写出的错误数
+    pub(crate) fn unreported(&self) -> usize {
+        self.unreported
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// 诊断信息中显示的输入名称：成对检查按 `label` 取 R1 或 R2 的名称（配对错误的位置是 R1 的行）
+fn source_name<'a>(options: &'a CheckOptions, label: Option<&'a str>) -> Option<&'a str> {
+    match (&options.pair_source_names, label) {
+        (Some((r1, _)), Some("R1" | "R1/R2")) => Some(r1),
+        (Some((_, r2)), Some("R2")) => Some(r2),
+        _ => options.source_name.as_deref().or(label),
+    }
+}
+
+/// 错误记录的读段名，取自第一行（标题行）
+fn read_name(lines: &[&[u8]]) -> Option<String> {
+    match lines.first() {
+        Some(header) if header.starts_with(b"@") => {
+            Some(String::from_utf8_lossy(header_read_id(header)).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// 错误中记录的具体取值
+fn error_fields(err: &FastqError) -> Value {
+    match err {
+        FastqError::InvalidHeader(_)
+        | FastqError::InvalidPlusLine(_)
+        | FastqError::PlusLineNotBare(_)
+        | FastqError::FilteredRead(_)
+        | FastqError::CrlfLineEnding(_) => json!({}),
+        FastqError::PlusLineMismatch { header, plus, .. } => json!({ "header": header, "plus": plus }),
+        FastqError::LengthMismatch { seq_len, qual_len, .. } => {
+            json!({ "seq_len": seq_len, "qual_len": qual_len })
+        }
+        FastqError::InvalidBase { column, byte, .. } | FastqError::LowercaseBase { column, byte, .. } => {
+            json!({ "column": column, "byte": byte.escape_ascii().to_string() })
+        }
+        FastqError::InvalidQuality { column, byte, encoding, .. } => json!({
+            "column": column,
+            "byte": byte.escape_ascii().to_string(),
+            "encoding": encoding.to_string(),
+        }),
+        FastqError::DuplicateReadId { name, first_line, .. } => {
+            json!({ "name": name, "first_line": first_line })
+        }
+        FastqError::PairNameMismatch { r1_line, r2_line, r1_name, r2_name } => json!({
+            "r1_line": r1_line,
+            "r2_line": r2_line,
+            "r1_name": r1_name,
+            "r2_name": r2_name,
+        }),
+        FastqError::PairCountMismatch { r1_records, r2_records } => {
+            json!({ "r1_records": r1_records, "r2_records": r2_records })
+        }
+        FastqError::OrphanRead { name, .. } => json!({ "name": name }),
+        FastqError::MateOrder { name, expected, found, .. } => {
+            json!({ "name": name, "expected": expected, "found": found })
+        }
+        FastqError::MalformedHeader { field, value, .. } => json!({ "field": field, "value": value }),
+        FastqError::IndexLengthMismatch { length, expected, .. } => {
+            json!({ "length": length, "expected": expected })
+        }
+        FastqError::TruncatedRecord { lines_present, .. } => json!({ "lines_present": lines_present }),
+        FastqError::SkippedLines { first, last } => json!({ "first": first, "last": last }),
+        FastqError::RuleViolation { rule, .. } => json!({ "rule": rule }),
+        FastqError::ReadTooShort { length, min, .. } => json!({ "length": length, "min": min }),
+        FastqError::ReadTooLong { length, max, .. } => json!({ "length": length, "max": max }),
+        FastqError::Io(e) => json!({ "error": e.to_string() }),
+        FastqError::Format(msg) => json!({ "error": msg }),
+    }
+}
+
+/// TSV 字段中的制表符和换行符转义为 `\t`、`\n`
+fn tsv_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
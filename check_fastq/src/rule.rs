@@ -0,0 +1,395 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Alphabet, CheckOptions, FastqError, FastqRecordRef, IlluminaHeader, Platform, QualityEncoding, Severity};
+
+/// 规则发现的一个问题及其严重程度，自定义规则的问题使用 `FastqError::RuleViolation`
+///
+/// 通常用 `RecordContext::violation` 按配置决定严重程度；规则也可以用 `Violation::new` 自行指定，
+/// 但配置中（`CheckOptions::severity`）明确设置了该类别的严重程度时仍以配置为准。
+#[derive(Debug)]
+pub struct Violation {
+    pub error: FastqError,
+    pub severity: Severity,
+}
+
+impl Violation {
+    pub fn new(error: FastqError, severity: Severity) -> Self {
+        Violation { error, severity }
+    }
+}
+
+/// 检查单条记录的验证规则
+///
+/// 规则本身不保存状态（需要时可以使用内部可变性），同一个规则会用于输入中的每一条记录。
+///
+/// `check` 接收借用读取缓冲区的 `FastqRecordRef` 而不是 `&FastqRecord`，检查时不必为每条记录复制四行；
+/// 已有 `FastqRecord` 时用 `FastqRecord::as_record_ref` 转换。
+///
+/// ```
+/// use check_fastq::{FastqError, FastqRecordRef, RecordContext, Rule, Severity, Violation};
+///
+/// /// 读段名必须以 `SAMPLE_` 开头
+/// struct SamplePrefix;
+///
+/// impl Rule for SamplePrefix {
+///     fn name(&self) -> &'static str {
+///         "sample_prefix"
+///     }
+///
+///     fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+///         if record.read_id().starts_with(b"SAMPLE_") {
+///             return Vec::new();
+///         }
+///         let error = FastqError::RuleViolation {
+///             rule: self.name(),
+///             line: ctx.line,
+///             message: "read name lacks the SAMPLE_ prefix".to_string(),
+///         };
+///         // 小写的前缀只作为警告，其余按配置（默认为错误）
+///         match record.read_id().starts_with(b"sample_") {
+///             true => vec![Violation::new(error, Severity::Warning)],
+///             false => vec![ctx.violation(error)],
+///         }
+///     }
+/// }
+/// ```
+pub trait Rule: Send + Sync {
+    /// 规则名称（snake_case），自定义规则同时以它作为 `FastqError::RuleViolation` 的错误类别
+    ///
+    /// 内置规则只报告一类错误时以该类别命名，报告多类错误时另取名称（如 `plus_line`），见 `kinds`。
+    fn name(&self) -> &'static str;
+
+    /// 规则可能报告的错误类别，按规则名称设置严重程度（配置的 `[rules]`、`--fail-on`）时使用
+    fn kinds(&self) -> Vec<&'static str> {
+        vec![self.name()]
+    }
+
+    /// 检查一条记录，返回发现的所有问题，没有问题时返回空列表
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation>;
+}
+
+/// 检查一条记录时可用的上下文
+#[derive(Debug, Clone, Copy)]
+pub struct RecordContext<'a> {
+    /// 标题行行号，记录的其他行依次为 `line + 1` 到 `line + 3`
+    pub line: usize,
+    /// 本次检查的选项
+    pub options: &'a CheckOptions,
+    /// 标题行应遵循的测序平台格式，`Platform::Auto` 已按该记录的标题行识别，无法识别或不检查时为 `None`
+    pub platform: Option<Platform>,
+}
+
+impl<'a> RecordContext<'a> {
+    /// `line` 为标题行行号，自动识别平台时按 `record` 的标题行识别
+    pub fn new(record: &FastqRecordRef<'_>, line: usize, options: &'a CheckOptions) -> Self {
+        let platform = match options.platform {
+            Some(Platform::Auto) => Platform::detect(record.header),
+            other => other,
+        };
+        RecordContext { line, options, platform }
+    }
+
+    /// 严重程度按配置（`CheckOptions::severity_of`）决定的问题
+    pub fn violation(&self, error: FastqError) -> Violation {
+        let severity = self.options.severity_of(error.kind());
+        Violation::new(error, severity)
+    }
+}
+
+/// 按顺序执行的一组验证规则
+///
+/// `Validator::new()` 包含所有内置规则，`Validator::empty()` 不含任何规则。用 `rule` 添加自定义规则后
+/// 放入 `CheckOptions::validator`，文件检查和成对检查就会对每条记录执行这些规则，如
+/// `CheckOptions { validator: Validator::new().rule(BarcodeWhitelist::load(path)?), ..Default::default() }`。
+#[derive(Clone)]
+pub struct Validator {
+    rules: Vec<Arc<dyn Rule>>,
+}
+
+impl Validator {
+    /// 包含所有内置规则的验证器，规则的顺序即错误报告的顺序
+    pub fn new() -> Self {
+        Validator::empty()
+            .rule(HeaderRule)
+            .rule(PlusLineRule)
+            .rule(LengthRule)
+            .rule(PlatformHeaderRule)
+            .rule(FilterFlagRule)
+            .rule(QualityRule)
+            .rule(AlphabetRule)
+            .rule(LowercaseRule)
+            .rule(ReadLengthRule)
+    }
+
+    /// 不含任何规则的验证器
+    pub fn empty() -> Self {
+        Validator { rules: Vec::new() }
+    }
+
+    /// 在最后添加一条规则
+    pub fn rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    /// 去掉指定名称的规则
+    pub fn without(mut self, name: &str) -> Self {
+        self.rules.retain(|rule| rule.name() != name);
+        self
+    }
+
+    /// 各条规则的名称
+    pub fn rule_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|rule| rule.name())
+    }
+
+    /// 按错误类别、错误代码（不区分大小写）或规则名称查找错误类别
+    ///
+    /// 规则名称对应该规则报告的所有类别，如 `plus_line` 对应 `invalid_plus_line`、`plus_line_mismatch`
+    /// 与 `plus_line_not_bare`。都找不到时返回 `None`。
+    pub fn resolve_kinds(&self, name: &str) -> Option<Vec<&'static str>> {
+        if let Some(kind) = FastqError::kind_by_name(name) {
+            return Some(vec![kind]);
+        }
+        self.rules.iter().find(|rule| rule.name() == name).map(|rule| rule.kinds())
+    }
+
+    /// 依次执行所有规则，返回发现的所有问题
+    ///
+    /// 配置中明确设置了严重程度的类别以配置为准，`Severity::Off` 的问题不返回。
+    pub fn validate(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for rule in &self.rules {
+            for mut violation in rule.check(record, ctx) {
+                if let Some(&severity) = ctx.options.severity.get(violation.error.kind()) {
+                    violation.severity = severity;
+                }
+                if violation.severity != Severity::Off {
+                    violations.push(violation);
+                }
+            }
+        }
+        violations
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Validator::new()
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.rule_names()).finish()
+    }
+}
+
+/// 标题行必须以 `@` 开头
+pub struct HeaderRule;
+
+impl Rule for HeaderRule {
+    fn name(&self) -> &'static str {
+        "invalid_header"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        if record.header.starts_with(b"@") {
+            return Vec::new();
+        }
+        vec![ctx.violation(FastqError::InvalidHeader(ctx.line))]
+    }
+}
+
+/// 加号行必须以 `+` 开头，之后的内容须与标题行相同（或按选项要求只有 `+`）
+///
+/// 标题行不以 `@` 开头时不比较标识符。与标题行相同的标识符报告为 `PlusLineNotBare`，
+/// 其严重程度见 `CheckOptions::severity_of`。
+pub struct PlusLineRule;
+
+impl Rule for PlusLineRule {
+    fn name(&self) -> &'static str {
+        "plus_line"
+    }
+
+    fn kinds(&self) -> Vec<&'static str> {
+        vec!["invalid_plus_line", "plus_line_mismatch", "plus_line_not_bare"]
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let line = ctx.line + 2;
+        let Some(plus_id) = record.plus_line.strip_prefix(b"+") else {
+            return vec![ctx.violation(FastqError::InvalidPlusLine(line))];
+        };
+        if plus_id.is_empty() {
+            return Vec::new();
+        }
+        if ctx.options.require_bare_plus {
+            return vec![ctx.violation(FastqError::PlusLineNotBare(line))];
+        }
+
+        match record.header.strip_prefix(b"@") {
+            Some(header_id) if plus_id != header_id => vec![ctx.violation(FastqError::PlusLineMismatch {
+                line,
+                header: String::from_utf8_lossy(header_id).into_owned(),
+                plus: String::from_utf8_lossy(plus_id).into_owned(),
+            })],
+            _ => vec![ctx.violation(FastqError::PlusLineNotBare(line))],
+        }
+    }
+}
+
+/// 序列与质量值的长度必须相同
+pub struct LengthRule;
+
+impl Rule for LengthRule {
+    fn name(&self) -> &'static str {
+        "length_mismatch"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        if record.sequence.len() == record.quality.len() {
+            return Vec::new();
+        }
+        vec![ctx.violation(FastqError::LengthMismatch {
+            seq_len: record.sequence.len(),
+            qual_len: record.quality.len(),
+            line_num: ctx.line + 3,
+        })]
+    }
+}
+
+/// 标题行须符合 `RecordContext::platform` 的格式，未指定平台时不检查
+pub struct PlatformHeaderRule;
+
+impl Rule for PlatformHeaderRule {
+    fn name(&self) -> &'static str {
+        "malformed_header"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let Some(platform) = ctx.platform else {
+            return Vec::new();
+        };
+        if !record.header.starts_with(b"@") {
+            return Vec::new();
+        }
+        match record.platform_header(platform) {
+            Ok(_) => Vec::new(),
+            Err(err) => vec![ctx.violation(FastqError::MalformedHeader {
+                line: ctx.line,
+                field: err.field,
+                value: err.value,
+            })],
+        }
+    }
+}
+
+/// 开启 `CheckOptions::flag_filtered` 时报告 Illumina 过滤标记为 `Y` 的读段
+pub struct FilterFlagRule;
+
+impl Rule for FilterFlagRule {
+    fn name(&self) -> &'static str {
+        "filtered_read"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        if ctx.options.flag_filtered && IlluminaHeader::parse(record.header).is_ok_and(|h| h.filtered) {
+            return vec![ctx.violation(FastqError::FilteredRead(ctx.line))];
+        }
+        Vec::new()
+    }
+}
+
+/// 质量值须在编码范围内；未指定编码时只要求是可打印字符
+pub struct QualityRule;
+
+impl Rule for QualityRule {
+    fn name(&self) -> &'static str {
+        "invalid_quality"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let encoding = ctx.options.quality_encoding.unwrap_or(QualityEncoding::Phred33);
+        match encoding.first_invalid(record.quality) {
+            Some(pos) => vec![ctx.violation(FastqError::InvalidQuality {
+                line: ctx.line + 3,
+                column: pos + 1,
+                byte: record.quality[pos],
+                encoding,
+            })],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// 序列中的碱基须属于所选字母表
+pub struct AlphabetRule;
+
+impl Rule for AlphabetRule {
+    fn name(&self) -> &'static str {
+        "invalid_base"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let options = ctx.options;
+        match options.alphabet.first_invalid(record.sequence, options.case_sensitive) {
+            Some(pos) => vec![ctx.violation(FastqError::InvalidBase {
+                line: ctx.line + 1,
+                column: pos + 1,
+                byte: record.sequence[pos],
+            })],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// 不区分大小写时报告序列中第一个小写碱基
+///
+/// 区分大小写时小写碱基已由 `AlphabetRule` 报告为非法；不属于字母表的小写字符同样由 `AlphabetRule` 报告。
+pub struct LowercaseRule;
+
+impl Rule for LowercaseRule {
+    fn name(&self) -> &'static str {
+        "lowercase_base"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let options = ctx.options;
+        if options.case_sensitive || options.alphabet == Alphabet::Any {
+            return Vec::new();
+        }
+        let lowercase = |&base: &u8| base.is_ascii_lowercase() && options.alphabet.accepts(base, false);
+        match record.sequence.iter().position(lowercase) {
+            Some(pos) => vec![ctx.violation(FastqError::LowercaseBase {
+                line: ctx.line + 1,
+                column: pos + 1,
+                byte: record.sequence[pos],
+            })],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// 序列长度须在 `CheckOptions::min_length` 与 `max_length` 之间，未设置时不检查
+pub struct ReadLengthRule;
+
+impl Rule for ReadLengthRule {
+    fn name(&self) -> &'static str {
+        "read_length"
+    }
+
+    fn kinds(&self) -> Vec<&'static str> {
+        vec!["read_too_short", "read_too_long"]
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let (line, length) = (ctx.line + 1, record.sequence.len());
+        match (ctx.options.min_length, ctx.options.max_length) {
+            (Some(min), _) if length < min => vec![ctx.violation(FastqError::ReadTooShort { line, length, min })],
+            (_, Some(max)) if length > max => vec![ctx.violation(FastqError::ReadTooLong { line, length, max })],
+            _ => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::PathBuf;
+
+use check_fastq::{check_fastq_file, check_fastq_path, CheckOptions, DuplicateCheck, QualityEncoding};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data").join(name)
+}
+
+/// 在临时目录写一个测试文件，文件名带上进程号以免并行测试互相覆盖
+fn temp_fastq(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("check_fastq_{}_{}.fastq", std::process::id(), name));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+fn records(names: &[&str]) -> String {
+    names.iter().map(|name| format!("@{}\nACGT\n+\nIIII\n", name)).collect()
+}
+
+#[test]
+fn counts_truncated_record_once() {
+    let input = "@a\nACGT\n+\nIIII\n@b\nACGT\n";
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.records, 2);
+    assert_eq!(summary.errors, 1);
+    assert_eq!(summary.errors_by_kind.get("truncated_record"), Some(&1));
+    assert_eq!(summary.first_error.unwrap().line, Some(5));
+}
+
+#[test]
+fn resync_continues_after_a_misframed_record() {
+    let input = "@a\nACGT\n+\nIIII\n@b\nACGT\nEXTRA\n+\nIIII\n@c\nACGT\n+\nIIII\n";
+    let options = CheckOptions { resync: true, ..CheckOptions::default() };
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!(summary.records, 2);
+    assert_eq!(summary.errors_by_kind.get("skipped_lines"), Some(&1));
+}
+
+#[test]
+fn exact_and_bloom_duplicates_agree() {
+    let path = temp_fastq("duplicates", &records(&["a", "b", "a", "c", "b", "a"]));
+    for duplicates in [DuplicateCheck::Exact, DuplicateCheck::Bloom { memory: 1 << 20 }] {
+        let options = CheckOptions { duplicates, ..CheckOptions::default() };
+        let summary = check_fastq_path(&path, None, &options).unwrap();
+        assert_eq!(summary.errors_by_kind.get("duplicate_read_id"), Some(&3), "{:?}", duplicates);
+        assert!(!summary.stopped_early);
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn bloom_needs_a_reopenable_input() {
+    let options = CheckOptions { duplicates: DuplicateCheck::Bloom { memory: 1 << 20 }, ..CheckOptions::default() };
+    let input = records(&["a", "a"]);
+    assert!(check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &options).is_err());
+}
+
+#[test]
+fn bloom_confirmation_honours_max_errors() {
+    let path = temp_fastq("bloom_limit", &records(&["a", "b", "a", "b", "a"]));
+    let options = CheckOptions {
+        duplicates: DuplicateCheck::Bloom { memory: 1 << 20 },
+        max_errors: Some(1),
+        ..CheckOptions::default()
+    };
+    let summary = check_fastq_path(&path, None, &options).unwrap();
+    assert_eq!(summary.errors, 1);
+    assert!(summary.stopped_early);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn crlf_line_endings_are_a_warning() {
+    let input = "@a\r\nACGT\r\n+\r\nIIII\r\n";
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.records, 1);
+    assert_eq!(summary.errors, 0);
+    assert_eq!(summary.warnings_by_kind.get("crlf_line_ending"), Some(&1));
+}
+
+#[test]
+fn valid_fixture_passes() {
+    let summary = check_fastq_path(fixture("valid.fastq"), None, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.records, 2);
+    assert_eq!(summary.errors, 0);
+    assert!(summary.first_error.is_none());
+}
+
+#[test]
+fn invalid_fixture_reports_each_error() {
+    let summary = check_fastq_path(fixture("invalid.fastq"), None, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.records, 3);
+    assert_eq!(summary.errors, 3);
+    assert_eq!(summary.errors_by_kind.get("length_mismatch"), Some(&2));
+    assert_eq!(summary.errors_by_kind.get("invalid_header"), Some(&1));
+    let first = summary.first_error.unwrap();
+    assert_eq!((first.code, first.line), ("FQ005", Some(4)));
+}
+
+#[test]
+fn error_output_matches_reference() {
+    let output = std::env::temp_dir().join(format!("check_fastq_{}_errors.txt", std::process::id()));
+    check_fastq_path(fixture("invalid.fastq"), Some(output.clone()), &CheckOptions::default()).unwrap();
+    let expected = fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("errors.txt")).unwrap();
+    assert_eq!(fs::read_to_string(&output).unwrap(), expected);
+    fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn detected_quality_encoding_is_not_enforced() {
+    // 开头全是高质量值（可能被误判为 Phred+64），之后出现低于 @ 的字符
+    let mut input = "@r\nACGT\n+\nFFF~\n".repeat(10_001);
+    input.push_str("@s\nACGT\n+\n5555\n");
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.errors, 0);
+    assert_eq!(summary.quality_encoding, Some(QualityEncoding::Phred33));
+
+    let input = "@r\nACGT\n+\n@Ihh\n".repeat(3);
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &CheckOptions::default()).unwrap();
+    assert_eq!(summary.quality_encoding, Some(QualityEncoding::Phred64));
+}
+
+#[test]
+fn specified_quality_encoding_is_enforced() {
+    let input = "@r\nACGT\n+\nhh5h\n";
+    let options = CheckOptions { quality_encoding: Some(QualityEncoding::Phred64), ..CheckOptions::default() };
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!(summary.errors_by_kind.get("invalid_quality"), Some(&1));
+    assert_eq!(summary.quality_encoding, Some(QualityEncoding::Phred64));
+}
+
+fn interleaved(options: CheckOptions) -> CheckOptions {
+    CheckOptions { interleaved: true, ..options }
+}
+
+#[test]
+fn interleaved_reports_orphans_and_mate_order() {
+    let input = records(&["a/1", "a/2", "b/1", "c/1", "c/2", "d/2", "d/1", "e/1"]);
+    let mut out = Vec::new();
+    let summary = check_fastq_file(input.as_bytes(), Some(&mut out), &interleaved(CheckOptions::default())).unwrap();
+    assert_eq!(summary.errors_by_kind.get("orphan_read"), Some(&2));
+    assert_eq!(summary.errors_by_kind.get("mate_order"), Some(&2));
+    // b/1 在 c/1 之前没有配对，e/1 在输入结束时没有配对
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("[FQ011]").count(), 2);
+    assert!(out.contains("@b/1") && out.contains("@e/1"));
+}
+
+#[test]
+fn interleaved_mates_are_not_duplicates() {
+    let casava = ["r1 1:N:0:ACGT", "r1 2:N:0:ACGT", "r2 1:N:0:ACGT", "r2 2:N:0:ACGT"];
+    let path = temp_fastq("interleaved", &records(&casava));
+    for duplicates in [DuplicateCheck::Exact, DuplicateCheck::Bloom { memory: 1 << 20 }] {
+        let options = interleaved(CheckOptions { duplicates, ..CheckOptions::default() });
+        let summary = check_fastq_path(&path, None, &options).unwrap();
+        assert_eq!(summary.errors, 0, "{:?}", duplicates);
+    }
+    fs::remove_file(path).unwrap();
+
+    // 同一对读段出现两次时两端都是重复
+    let input = records(&["a/1", "a/2", "b/1", "b/2", "a/1", "a/2"]);
+    let options = interleaved(CheckOptions { duplicates: DuplicateCheck::Exact, ..CheckOptions::default() });
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!(summary.errors_by_kind.get("duplicate_read_id"), Some(&2));
+}
+
+#[test]
+fn max_errors_stops_checking_and_per_kind_limits_only_the_report() {
+    let input = "@a\nACGT\n+\nII\n@b\nACGT\n+\nII\n@c\nACGT\n+\nII\n";
+    let options = CheckOptions { max_errors: Some(2), ..CheckOptions::default() };
+    let summary = check_fastq_file(input.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!((summary.records, summary.errors), (2, 2));
+    assert!(summary.stopped_early);
+
+    let mut report = Vec::new();
+    let options = CheckOptions { max_errors_per_kind: Some(1), ..CheckOptions::default() };
+    let summary = check_fastq_file(input.as_bytes(), Some(&mut report), &options).unwrap();
+    assert_eq!((summary.records, summary.errors), (3, 3));
+    assert!(!summary.stopped_early);
+    assert_eq!(summary.unreported_errors, 2);
+    assert_eq!(String::from_utf8(report).unwrap().matches("---").count(), 1);
+}
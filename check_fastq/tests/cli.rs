@@ -0,0 +1,161 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// 在临时目录写一个测试文件，文件名带上进程号以免并行测试互相覆盖
+fn temp_file(name: &str, text: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("check_fastq_cli_{}_{}", std::process::id(), name));
+    fs::write(&path, text).unwrap();
+    path
+}
+
+/// 运行命令行程序，`stdin` 为标准输入的内容；语言固定为中文，不受测试环境的 LANG 影响
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_check_fastq"))
+        .args(args)
+        .env("LANG", "zh_CN.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn check_pair_reads_one_mate_from_stdin() {
+    let r2 = temp_file("stdin_r2.fq", "@a/2\nACGT\n+\nIIII\n@b/2\nACGT\n+\nIIII\n");
+    let r1 = "@a/1\nACGT\n+\nIIII\n@c/1\nACGT\n+\nIIII\n";
+    let output = run(&["check-pair", "-1", "-", "-2", r2.to_str().unwrap()], r1);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("R1 与 R2 从 R1 行 5 / R2 行 5 开始不同步"));
+    fs::remove_file(r2).unwrap();
+}
+
+#[test]
+fn check_pair_rejects_stdin_for_both_mates() {
+    let output = run(&["check-pair", "-1", "-", "-2", "-"], "");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+fn read_json(path: &PathBuf) -> serde_json::Value {
+    let value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    fs::remove_file(path).unwrap();
+    value
+}
+
+#[test]
+fn writes_summary_json_and_multiqc_table() {
+    let input = temp_file("summary.fastq", "@a\nACGT\n+\nIII\n@b\nACGTAC\n+\nIIIIII\n");
+    let summary = std::env::temp_dir().join(format!("check_fastq_cli_{}_summary.json", std::process::id()));
+    let multiqc = std::env::temp_dir().join(format!("check_fastq_cli_{}_mqc.json", std::process::id()));
+    let output = run(
+        &[
+            "check",
+            "-i",
+            input.to_str().unwrap(),
+            "--summary-json",
+            summary.to_str().unwrap(),
+            "--multiqc",
+            multiqc.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let summary = read_json(&summary);
+    assert_eq!(summary["input"], input.to_str().unwrap());
+    assert_eq!(summary["verdict"], "fail");
+    assert_eq!(summary["records"], 2);
+    assert_eq!(summary["bases"], 10);
+    assert_eq!(summary["read_length_range"], serde_json::json!({ "min": 4, "max": 6 }));
+    assert_eq!(summary["errors"], 1);
+    assert_eq!(summary["errors_by_kind"]["length_mismatch"], 1);
+    assert_eq!(summary["first_error"], serde_json::json!({ "code": "FQ005", "kind": "length_mismatch", "line": 4 }));
+    assert_eq!(summary["quality_encoding"], "Phred+33");
+    assert_eq!(summary["quality_encoding_detected"], true);
+    assert_eq!(summary["stopped_early"], false);
+    assert!(summary["elapsed_seconds"].is_number());
+
+    let multiqc = read_json(&multiqc);
+    assert_eq!(multiqc["plot_type"], "table");
+    let sample = input.file_name().unwrap().to_str().unwrap().strip_suffix(".fastq").unwrap();
+    assert_eq!(multiqc["data"][sample]["records"], 2);
+    assert_eq!(multiqc["data"][sample]["verdict"], "fail");
+    fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn exit_codes_follow_the_failing_kinds() {
+    let valid = temp_file("valid.fastq", "@a\nACGT\n+\nIIII\n");
+    let invalid = temp_file("invalid.fastq", "@a\nACGT\n+\nIII\n@b\nACGT\n");
+    let lowercase = temp_file("lowercase.fastq", "@a\nacgt\n+\nIIII\n");
+    let code = |args: &[&str]| run(args, "").status.code();
+    let (valid, invalid, lowercase) = (valid.to_str().unwrap(), invalid.to_str().unwrap(), lowercase.to_str().unwrap());
+
+    assert_eq!(code(&["check", "-i", valid]), Some(0));
+    // 输入被截断优先于其他错误
+    assert_eq!(code(&["check", "-i", invalid]), Some(4));
+    assert_eq!(code(&["check", "-i", invalid, "--fail-on", "length_mismatch"]), Some(1));
+    assert_eq!(code(&["check", "-i", invalid, "--fail-on", "FQ006"]), Some(0));
+    // 警告默认不导致失败，用 --fail-on 选中后导致失败
+    assert_eq!(code(&["check", "-i", lowercase]), Some(0));
+    assert_eq!(code(&["check", "-i", lowercase, "--fail-on", "lowercase_base"]), Some(1));
+    assert_eq!(code(&["check", "-i", "/nonexistent/reads.fastq"]), Some(2));
+    assert_eq!(code(&["check", "-i", valid, "--fail-on", "bogus"]), Some(3));
+    assert_eq!(code(&["check", "-i", valid, "--max-errors", "0"]), Some(3));
+
+    for path in [valid, invalid, lowercase] {
+        fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn fail_on_rejects_kinds_that_never_reach_the_counts() {
+    let output = run(&["check", "-i", "-", "--fail-on", "io"], "");
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("未知的错误类别: io"));
+    assert!(stderr.contains("length_mismatch") && stderr.contains("crlf_line_ending）"));
+    assert!(!stderr.contains(", io") && !stderr.contains(", format"));
+}
+
+fn has_chinese(text: &str) -> bool {
+    text.chars().any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c))
+}
+
+#[test]
+fn help_follows_the_language() {
+    let english: [&[&str]; 3] =
+        [&["--lang", "en", "--help"], &["--lang", "en", "check", "--help"], &["check-pair", "--help", "--lang=en"]];
+    for args in english {
+        let help = stdout(&run(args, ""));
+        assert!(help.contains("Usage:"), "{:?}", args);
+        assert!(!has_chinese(&help), "{:?}: {}", args, help);
+    }
+    let help = stdout(&run(&["check", "--help"], ""));
+    assert!(help.contains("输入的 FASTQ 文件路径"));
+}
+
+#[test]
+fn messages_and_text_report_follow_the_language() {
+    let input = temp_file("lang.fastq", "@a\nACGT\n+\nIII\n");
+    let path = input.to_str().unwrap();
+    let output = run(&["check", "-i", path, "-o", "-", "--lang", "en"], "");
+    assert!(stdout(&output).starts_with("error [FQ005]: sequence length (4) does not match quality length (3)"));
+    let log = String::from_utf8_lossy(&output.stderr);
+    assert!(log.contains("Records processed: 1") && !has_chinese(&log), "{}", log);
+
+    let output = run(&["check", "-i", path, "-o", "-"], "");
+    assert!(stdout(&output).starts_with("错误 [FQ005]: 序列长度 (4) 与质量值长度 (3) 不匹配"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("处理的记录总数: 1"));
+    fs::remove_file(input).unwrap();
+}
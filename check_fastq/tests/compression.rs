@@ -0,0 +1,71 @@
+use std::io::{BufRead, Cursor, Write};
+
+use check_fastq::{check_fastq_file, decompress, CheckOptions, Compression};
+
+const FIRST: &[u8] = b"@a\nACGT\n+\nIIII\n@b\nACGT\n+\nIIII\n";
+const SECOND: &[u8] = b"@c\nACGT\n+\nIIII\n";
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn bzip2(data: &[u8]) -> Vec<u8> {
+    let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn xz(data: &[u8]) -> Vec<u8> {
+    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zstd(data: &[u8]) -> Vec<u8> {
+    zstd::encode_all(data, 0).unwrap()
+}
+
+/// 把两段数据分别压缩后首尾相接，得到多成员（multi-member / 多帧）的压缩流
+fn concatenated(compress: fn(&[u8]) -> Vec<u8>) -> Vec<u8> {
+    let mut data = compress(FIRST);
+    data.extend(compress(SECOND));
+    data
+}
+
+fn assert_decompresses(data: Vec<u8>, expected: Compression) {
+    let (mut reader, compression) = decompress(Cursor::new(data.clone())).unwrap();
+    assert_eq!(compression, expected);
+    let mut text = Vec::new();
+    while reader.read_until(b'\n', &mut text).unwrap() > 0 {}
+    assert_eq!(text, [FIRST, SECOND].concat());
+
+    let summary = check_fastq_file(Cursor::new(data), None::<Vec<u8>>, &CheckOptions::default()).unwrap();
+    assert_eq!((summary.records, summary.errors), (3, 0));
+}
+
+#[test]
+fn reads_plain_input() {
+    assert_decompresses([FIRST, SECOND].concat(), Compression::None);
+}
+
+#[test]
+fn reads_multi_member_gzip() {
+    assert_decompresses(concatenated(gzip), Compression::Gzip);
+}
+
+#[test]
+fn reads_multi_stream_bzip2() {
+    assert_decompresses(concatenated(bzip2), Compression::Bzip2);
+}
+
+#[test]
+fn reads_multi_stream_xz() {
+    assert_decompresses(concatenated(xz), Compression::Xz);
+}
+
+#[test]
+fn reads_multi_frame_zstd() {
+    assert_decompresses(concatenated(zstd), Compression::Zstd);
+}
@@ -0,0 +1,66 @@
+use check_fastq::{Diagnostic, FastqError, QualityEncoding, Severity};
+
+fn render(err: &FastqError, lines: &[&str]) -> String {
+    Diagnostic::new(err, lines.iter().map(|line| line.as_bytes())).source("reads.fq").to_string()
+}
+
+/// 标注行中 `^` 的起始列（从 0 开始，不含行号栏）与个数
+fn carets(rendered: &str) -> Vec<(usize, usize)> {
+    rendered
+        .lines()
+        .filter_map(|line| line.split_once(" | "))
+        .filter_map(|(gutter, text)| gutter.trim().is_empty().then_some(text))
+        .filter_map(|text| {
+            let start = text.find('^')?;
+            Some((start, text[start..].chars().take_while(|&c| c == '^').count()))
+        })
+        .collect()
+}
+
+#[test]
+fn points_at_the_invalid_base() {
+    let err = FastqError::InvalidBase { line: 2, column: 5, byte: b'X' };
+    let rendered = render(&err, &["@r1", "ACGTXACGT", "+", "IIIIIIIII"]);
+    assert!(rendered.starts_with("错误[FQ006]: "));
+    assert!(rendered.contains(" --> reads.fq:2:5\n"));
+    assert!(rendered.contains("2 | ACGTXACGT\n"));
+    assert_eq!(carets(&rendered), [(4, 1)]);
+}
+
+#[test]
+fn points_at_the_invalid_quality() {
+    let err = FastqError::InvalidQuality { line: 8, column: 3, byte: b' ', encoding: QualityEncoding::Phred33 };
+    let rendered = render(&err, &["@r1", "ACGT", "+", "II I"]);
+    assert!(rendered.contains(" --> reads.fq:8:3\n"));
+    assert!(rendered.contains("8 | II I\n"));
+    assert_eq!(carets(&rendered), [(2, 1)]);
+}
+
+#[test]
+fn plus_line_mismatch_marks_where_the_identifiers_diverge() {
+    let err = FastqError::PlusLineMismatch { line: 3, header: "r1 c".into(), plus: "r1 x".into() };
+    let rendered = render(&err, &["@r1 c", "ACGT", "+r1 x", "IIII"]);
+    assert!(rendered.contains(" --> reads.fq:3:5\n"), "{}", rendered);
+    assert_eq!(carets(&rendered), [(4, 1)]);
+
+    let err = FastqError::PlusLineMismatch { line: 3, header: "r1".into(), plus: "r2 extra".into() };
+    let rendered = render(&err, &["@r1", "ACGT", "+r2 extra", "IIII"]);
+    assert_eq!(carets(&rendered), [(2, 7)]);
+}
+
+#[test]
+fn length_mismatch_marks_the_missing_quality() {
+    let err = FastqError::LengthMismatch { seq_len: 6, qual_len: 4, line_num: 4 };
+    let rendered = render(&err, &["@r1", "ACGTAC", "+", "IIII"]);
+    assert!(rendered.contains(" --> reads.fq:2:5\n"));
+    assert_eq!(carets(&rendered), [(4, 2)]);
+}
+
+#[test]
+fn warnings_use_their_own_title() {
+    let err = FastqError::LowercaseBase { line: 2, column: 1, byte: b'a' };
+    let rendered = Diagnostic::new(&err, [&b"@r1"[..], b"aCGT"]).severity(Severity::Warning).to_string();
+    assert!(rendered.starts_with("警告[FQ021]: "));
+    assert!(rendered.contains("<输入>:2:1"));
+    assert_eq!(carets(&rendered), [(0, 1)]);
+}
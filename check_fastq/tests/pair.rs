@@ -0,0 +1,86 @@
+use check_fastq::{check_fastq_pair, mate_name, read_number, CheckOptions, PairSummary, ReportFormat, Severity};
+
+fn records(names: &[&str]) -> String {
+    names.iter().map(|name| format!("@{}\nACGT\n+\nIIII\n", name)).collect()
+}
+
+fn check_pair(r1: &str, r2: &str, options: &CheckOptions) -> (PairSummary, String) {
+    let mut out = Vec::new();
+    let summary = check_fastq_pair(r1.as_bytes(), r2.as_bytes(), Some(&mut out), options).unwrap();
+    (summary, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn matches_mates_by_name() {
+    assert_eq!(mate_name(b"read1/1"), b"read1");
+    assert_eq!(mate_name(b"read1"), b"read1");
+    assert_eq!(read_number(b"@read1/2"), Some(2));
+    assert_eq!(read_number(b"@M1:1:FC:1:1:1:1 1:N:0:ACGT"), Some(1));
+    assert_eq!(read_number(b"@read1"), None);
+
+    let r1 = records(&["a/1", "b/1", "c 1:N:0:ACGT"]);
+    let r2 = records(&["a/2", "b/2", "c 2:N:0:ACGT"]);
+    let (summary, _) = check_pair(&r1, &r2, &CheckOptions::default());
+    assert_eq!((summary.r1.records, summary.r2.records), (3, 3));
+    assert_eq!(summary.errors(), 0);
+    assert_eq!(summary.first_desync, None);
+}
+
+#[test]
+fn reports_only_the_first_desync() {
+    let r1 = records(&["a/1", "b/1", "c/1", "d/1"]);
+    let r2 = records(&["a/2", "c/2", "d/2", "e/2"]);
+    let (summary, out) = check_pair(&r1, &r2, &CheckOptions::default());
+    assert_eq!(summary.first_desync, Some((5, 5)));
+    assert_eq!(summary.pair_errors, 1);
+    assert_eq!(summary.errors_by_kind.get("pair_name_mismatch"), Some(&1));
+    assert_eq!(summary.first_error.unwrap().code, "FQ009");
+    assert_eq!(out.matches("[FQ009]").count(), 1);
+}
+
+#[test]
+fn reports_record_count_mismatch() {
+    let r1 = records(&["a/1", "b/1", "c/1"]);
+    let r2 = records(&["a/2", "b/2"]);
+    let (summary, out) = check_pair(&r1, &r2, &CheckOptions::default());
+    assert_eq!((summary.r1.records, summary.r2.records), (3, 2));
+    assert_eq!(summary.errors_by_kind.get("pair_count_mismatch"), Some(&1));
+    assert_eq!(summary.first_desync, None);
+    assert!(out.contains("[FQ010]"));
+}
+
+#[test]
+fn pair_checks_follow_configured_severity() {
+    let r1 = records(&["a/1", "x/1", "c/1"]);
+    let r2 = records(&["a/2", "b/2"]);
+    let mut options = CheckOptions::default();
+    options.severity.insert("pair_name_mismatch", Severity::Off);
+    options.severity.insert("pair_count_mismatch", Severity::Warning);
+    let (summary, _) = check_pair(&r1, &r2, &options);
+    assert_eq!(summary.first_desync, None);
+    assert_eq!((summary.errors(), summary.pair_warnings), (0, 1));
+    assert_eq!(summary.warnings_by_kind.get("pair_count_mismatch"), Some(&1));
+}
+
+#[test]
+fn record_errors_are_counted_per_file() {
+    let r1 = records(&["a/1", "b/1"]);
+    let r2 = "@a/2\nACGT\n+\nIII\n@b/2\nACGT\n+\nIIII\n";
+    let (summary, out) = check_pair(&r1, r2, &CheckOptions::default());
+    assert_eq!((summary.r1.errors, summary.r2.errors, summary.pair_errors), (0, 1, 0));
+    assert!(out.contains("[FQ005] (R2)"));
+}
+
+#[test]
+fn pretty_diagnostics_name_the_input_files() {
+    let r1 = records(&["a/1", "b/1"]);
+    let r2 = "@a/2\nACGT\n+\nIIII\n@c/2\nACXT\n+\nIIII\n";
+    let options = CheckOptions {
+        report_format: ReportFormat::Pretty,
+        pair_source_names: Some(("sample_R1.fq".into(), "sample_R2.fq".into())),
+        ..CheckOptions::default()
+    };
+    let (_, out) = check_pair(&r1, r2, &options);
+    assert!(out.contains(" --> sample_R2.fq:6:3\n"), "{}", out);
+    assert!(out.contains(" --> sample_R1.fq:5:2\n"), "{}", out);
+}
@@ -0,0 +1,111 @@
+use check_fastq::{Alphabet, CheckOptions, DuplicateCheck, Platform, Profile, QualityEncoding, Severity};
+
+#[test]
+fn parses_options_and_rules() {
+    let profile = Profile::from_toml(
+        r#"
+        alphabet = "iupac"
+        case_sensitive = true
+        quality_encoding = "phred64"
+        platform = "illumina"
+        duplicates = "exact"
+        min_length = 50
+        max_length = 300
+        max_errors = 1000
+
+        [rules]
+        plus_line_mismatch = "warning"
+        FQ014 = "off"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(profile.alphabet, Some(Alphabet::Iupac));
+    assert_eq!(profile.case_sensitive, Some(true));
+    assert_eq!(profile.require_bare_plus, None);
+    assert_eq!(profile.quality_encoding, Some(QualityEncoding::Phred64));
+    assert_eq!(profile.platform, Some(Platform::Illumina));
+    assert_eq!(profile.duplicates, Some(DuplicateCheck::Exact));
+    assert_eq!((profile.min_length, profile.max_length, profile.max_errors), (Some(50), Some(300), Some(1000)));
+    assert_eq!(profile.severity.get("plus_line_mismatch"), Some(&Severity::Warning));
+    assert_eq!(profile.severity.get("filtered_read"), Some(&Severity::Off));
+}
+
+#[test]
+fn rule_names_set_every_kind_they_report() {
+    let profile = Profile::from_toml("[rules]\nplus_line = \"info\"\nread_length = \"off\"\n").unwrap();
+    let kinds: Vec<_> = profile.severity.iter().map(|(&kind, &severity)| (kind, severity)).collect();
+    assert_eq!(
+        kinds,
+        [
+            ("invalid_plus_line", Severity::Info),
+            ("plus_line_mismatch", Severity::Info),
+            ("plus_line_not_bare", Severity::Info),
+            ("read_too_long", Severity::Off),
+            ("read_too_short", Severity::Off),
+        ]
+    );
+}
+
+#[test]
+fn builtin_profiles() {
+    let strict = Profile::builtin("strict").unwrap();
+    assert_eq!(strict.alphabet, Some(Alphabet::Strict));
+    assert_eq!(strict.require_bare_plus, Some(true));
+    assert_eq!(strict.platform, Some(Platform::Auto));
+    assert_eq!(strict.severity.get("crlf_line_ending"), Some(&Severity::Error));
+
+    let lenient: Profile = "Lenient".parse().unwrap();
+    assert_eq!(lenient.alphabet, Some(Alphabet::Any));
+    assert_eq!(lenient.severity.get("plus_line_not_bare"), Some(&Severity::Off));
+
+    let default = Profile::builtin("default").unwrap();
+    assert!(default.alphabet.is_none() && default.severity.is_empty());
+    assert!(Profile::builtin("paranoid").is_none());
+}
+
+#[test]
+fn file_settings_override_the_base_profile() {
+    let profile = Profile::from_toml("profile = \"strict\"\nalphabet = \"iupac\"\n[rules]\ncrlf_line_ending = \"warning\"\n")
+        .unwrap();
+    assert_eq!(profile.alphabet, Some(Alphabet::Iupac));
+    assert_eq!(profile.case_sensitive, Some(true));
+    assert_eq!(profile.severity.get("crlf_line_ending"), Some(&Severity::Warning));
+}
+
+#[test]
+fn bloom_memory_is_given_in_mib() {
+    let profile = Profile::from_toml("duplicates = \"bloom\"\nbloom_memory = 64\n").unwrap();
+    assert_eq!(profile.duplicates, Some(DuplicateCheck::Bloom { memory: 64 * 1024 * 1024 }));
+}
+
+#[test]
+fn rejects_invalid_configuration() {
+    for text in [
+        "profile = \"paranoid\"\n",
+        "colour = true\n",
+        "alphabet = \"klingon\"\n",
+        "case_sensitive = \"yes\"\n",
+        "max_errors = 0\n",
+        "min_length = -1\n",
+        "rules = \"off\"\n",
+        "[rules]\nno_such_kind = \"off\"\n",
+        "[rules]\nlength_mismatch = \"fatal\"\n",
+        "alphabet = \n",
+    ] {
+        assert!(Profile::from_toml(text).is_err(), "accepted {:?}", text);
+    }
+    let err = Profile::from_toml("[rules]\nno_such_kind = \"off\"\n").unwrap_err();
+    assert!(err.contains("no_such_kind"), "{}", err);
+}
+
+#[test]
+fn apply_overrides_only_the_settings_present() {
+    let profile = Profile::from_toml("min_length = 20\nquality_encoding = \"phred33\"\n[rules]\nFQ005 = \"warning\"\n").unwrap();
+    let mut options = CheckOptions { alphabet: Alphabet::Rna, max_length: Some(100), ..CheckOptions::default() };
+    profile.apply(&mut options);
+    assert_eq!(options.alphabet, Alphabet::Rna);
+    assert_eq!((options.min_length, options.max_length), (Some(20), Some(100)));
+    assert_eq!(options.quality_encoding, Some(QualityEncoding::Phred33));
+    assert_eq!(options.severity_of("length_mismatch"), Severity::Warning);
+    assert_eq!(options.severity_of("invalid_base"), Severity::Error);
+}
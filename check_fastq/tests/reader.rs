@@ -0,0 +1,113 @@
+use std::io::Cursor;
+
+use check_fastq::{FastqError, FastqReader};
+
+fn reader(text: &str) -> FastqReader<Cursor<Vec<u8>>> {
+    FastqReader::new(Cursor::new(text.as_bytes().to_vec()))
+}
+
+/// 读完整个输入，返回每条记录的（标题行行号, 标题行）和遇到的错误
+fn read_all(mut reader: FastqReader<Cursor<Vec<u8>>>) -> (Vec<(usize, String)>, Vec<FastqError>) {
+    let (mut records, mut errors) = (Vec::new(), Vec::new());
+    loop {
+        match reader.read_next() {
+            Ok(true) => {
+                let header = String::from_utf8_lossy(reader.record().header).into_owned();
+                records.push((reader.line_num(), header));
+            }
+            Ok(false) => break,
+            Err(err) => errors.push(err),
+        }
+    }
+    (records, errors)
+}
+
+#[test]
+fn reads_fixture_records() {
+    let reader = FastqReader::from_path("tests/data/valid.fastq").unwrap();
+    let records: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].header, "@SEQ_ID1");
+    assert_eq!(records[0].sequence.len(), records[0].quality.len());
+}
+
+#[test]
+fn tracks_line_numbers_and_offsets() {
+    let mut reader = reader("@a\nAC\n+\nII\n@b\nGT\n+\nII\n");
+    assert!(reader.read_next().unwrap());
+    assert_eq!((reader.line_num(), reader.byte_offset()), (1, 0));
+    assert!(reader.read_next().unwrap());
+    assert_eq!((reader.line_num(), reader.byte_offset()), (5, 11));
+    assert!(!reader.read_next().unwrap());
+    assert_eq!((reader.lines_read(), reader.bytes_read()), (8, 22));
+}
+
+#[test]
+fn strips_crlf_and_remembers_first_crlf_line() {
+    let mut reader = reader("@a\nAC\n+\nII\n@b\r\nGT\r\n+\r\nII\r\n");
+    assert!(reader.read_next().unwrap());
+    assert_eq!(reader.crlf_line(), None);
+    assert!(reader.read_next().unwrap());
+    let record = reader.record();
+    assert_eq!(record.lines(), [&b"@b"[..], b"GT", b"+", b"II"]);
+    assert_eq!(reader.crlf_line(), Some(5));
+    assert_eq!(reader.bytes_read(), 26);
+}
+
+#[test]
+fn reports_truncated_record() {
+    let mut reader = reader("@a\nAC\n+\nII\n@b\nGT\n");
+    assert!(reader.read_next().unwrap());
+    match reader.read_next() {
+        Err(FastqError::TruncatedRecord { line, lines_present }) => assert_eq!((line, lines_present), (5, 2)),
+        other => panic!("unexpected {:?}", other),
+    }
+    assert_eq!(reader.raw_lines().collect::<Vec<_>>(), [&b"@b"[..], b"GT"]);
+    // 截断之后不再继续读取
+    assert!(!reader.read_next().unwrap());
+}
+
+#[test]
+fn resyncs_after_an_extra_line() {
+    let text = "@a\nAC\n+\nII\n@b\nGT\nEXTRA\n+\nII\n@c\nTT\n+\nII\n@d\nCC\n+\nII\n";
+    let (records, errors) = read_all(reader(text).with_resync(true));
+    assert!(matches!(errors[..], [FastqError::SkippedLines { first: 5, last: 9 }]));
+    assert_eq!(records, [(1, "@a".to_string()), (10, "@c".to_string()), (14, "@d".to_string())]);
+}
+
+#[test]
+fn resyncs_after_a_missing_line() {
+    // 第二条记录缺少序列行，向后搜索时预读的行要保留给下一条记录
+    let text = "@a\nAC\n+\nII\n@b\n+\nII\n@c\nTT\n+\nII\n";
+    let (records, errors) = read_all(reader(text).with_resync(true));
+    assert!(matches!(errors[..], [FastqError::SkippedLines { first: 5, last: 7 }]));
+    assert_eq!(records, [(1, "@a".to_string()), (8, "@c".to_string())]);
+}
+
+#[test]
+fn resync_skips_trailing_garbage() {
+    let (records, errors) = read_all(reader("@a\nAC\n+\nII\n@b\nGT\nX\nY\nZ\n").with_resync(true));
+    assert!(matches!(errors[..], [FastqError::SkippedLines { first: 5, last: 9 }]));
+    assert_eq!(records.len(), 1);
+}
+
+#[test]
+fn without_resync_misframed_records_are_returned_as_is() {
+    let (records, errors) = read_all(reader("@a\nAC\nX\n+\nII\n@b\nGT\n+\n"));
+    assert!(errors.is_empty());
+    assert_eq!(records, [(1, "@a".to_string()), (5, "II".to_string())]);
+}
+
+#[test]
+fn resync_keeps_only_the_first_skipped_lines() {
+    let mut text = String::from("@a\nAC\n+\nII\n@b\nGT\n");
+    text.push_str(&"X\n".repeat(10_000));
+    text.push_str("@c\nTT\n+\nII\n");
+    let mut reader = reader(&text).with_resync(true);
+    assert!(reader.read_next().unwrap());
+    assert!(matches!(reader.read_next(), Err(FastqError::SkippedLines { first: 5, last: 10_006 })));
+    assert_eq!(reader.raw_lines().count(), 8);
+    assert_eq!(reader.raw_lines().next(), Some(&b"@b"[..]));
+    assert!(reader.read_next().unwrap());
+    assert_eq!((reader.line_num(), reader.record().header), (10_007, &b"@c"[..]));
+}
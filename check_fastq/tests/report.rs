@@ -0,0 +1,60 @@
+use check_fastq::{check_fastq_file, CheckOptions, ReportFormat};
+use serde_json::Value;
+
+const INPUT: &str = "@r1 x\nACGT\n+\nIII\n@r2\nACXT\n+r2\nIIII\n";
+
+fn report(format: ReportFormat) -> String {
+    let options = CheckOptions { report_format: format, ..CheckOptions::default() };
+    let mut out = Vec::new();
+    check_fastq_file(INPUT.as_bytes(), Some(&mut out), &options).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn text_report_lists_messages_and_records() {
+    let text = report(ReportFormat::Text);
+    let blocks: Vec<&str> = text.split("---\n").collect();
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks[0].starts_with("错误 [FQ005]: 序列长度 (4) 与质量值长度 (3) 不匹配 (行 4)\n@r1 x\n"));
+    // 同一条记录的错误和警告写在一起，原始记录只写一次
+    assert_eq!(blocks[1].lines().filter(|line| line.starts_with("@r2")).count(), 1);
+    assert!(blocks[1].contains("错误 [FQ006]") && blocks[1].contains("警告 [FQ004]"));
+    assert!(!text.contains("LengthMismatch {"));
+}
+
+#[test]
+fn jsonl_report_has_one_object_per_error() {
+    let entries: Vec<Value> = report(ReportFormat::Jsonl)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 3);
+
+    let first = &entries[0];
+    assert_eq!(first["severity"], "error");
+    assert_eq!(first["code"], "FQ005");
+    assert_eq!(first["kind"], "length_mismatch");
+    assert_eq!(first["line"], 4);
+    assert_eq!(first["byte_offset"], 0);
+    assert_eq!(first["read_name"], "r1");
+    assert_eq!(first["fields"]["seq_len"], 4);
+    assert_eq!(first["fields"]["qual_len"], 3);
+    assert_eq!(first["record"][0], "@r1 x");
+
+    let codes: Vec<&str> = entries.iter().map(|e| e["code"].as_str().unwrap()).collect();
+    assert_eq!(codes, ["FQ005", "FQ004", "FQ006"]);
+    assert_eq!(entries[1]["severity"], "warning");
+    assert_eq!(entries[2]["byte_offset"], 17);
+    assert_eq!(entries[2]["fields"]["byte"], "X");
+}
+
+#[test]
+fn tsv_report_has_a_header_and_one_row_per_error() {
+    let tsv = report(ReportFormat::Tsv);
+    let rows: Vec<Vec<&str>> = tsv.lines().map(|line| line.split('\t').collect()).collect();
+    assert_eq!(rows[0], ["severity", "code", "kind", "input", "line", "byte_offset", "read_name", "message"]);
+    assert_eq!(rows.len(), 4);
+    assert!(rows.iter().all(|row| row.len() == 8));
+    assert_eq!(rows[1][..7], ["error", "FQ005", "length_mismatch", "", "4", "0", "r1"]);
+    assert_eq!(rows[3][..3], ["error", "FQ006", "invalid_base"]);
+}
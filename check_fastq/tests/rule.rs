@@ -0,0 +1,131 @@
+use check_fastq::{
+    check_fastq_file, validate_record_all, validate_record_with, CheckOptions, FastqError, FastqRecordRef,
+    RecordContext, ReportFormat, Rule, Severity, Validator, Violation,
+};
+
+/// 读段名必须以 `SAMPLE_` 开头，小写的 `sample_` 只是警告
+struct SamplePrefix;
+
+impl Rule for SamplePrefix {
+    fn name(&self) -> &'static str {
+        "sample_prefix"
+    }
+
+    fn check(&self, record: &FastqRecordRef<'_>, ctx: &RecordContext<'_>) -> Vec<Violation> {
+        let id = record.read_id();
+        if id.starts_with(b"SAMPLE_") {
+            return Vec::new();
+        }
+        let error = FastqError::RuleViolation {
+            rule: self.name(),
+            line: ctx.line,
+            message: format!("{} lacks the SAMPLE_ prefix", String::from_utf8_lossy(id)),
+        };
+        match id.starts_with(b"sample_") {
+            true => vec![Violation::new(error, Severity::Warning)],
+            false => vec![ctx.violation(error)],
+        }
+    }
+}
+
+const INPUT: &str = "@SAMPLE_1\nACGT\n+\nIIII\n@other\nACGT\n+\nIIII\n@SAMPLE_3\nACGT\n+\nIIII\n";
+
+fn options(validator: Validator) -> CheckOptions {
+    CheckOptions { validator, ..CheckOptions::default() }
+}
+
+#[test]
+fn custom_rule_runs_after_builtin_rules() {
+    let options = options(Validator::new().rule(SamplePrefix));
+    let rec = FastqRecordRef { header: b"@other", sequence: b"ACGT", plus_line: b"+", quality: b"II" };
+    let errors = validate_record_all(&rec, 5, &options);
+    let kinds: Vec<_> = errors.iter().map(|v| v.error.kind()).collect();
+    assert_eq!(kinds, ["length_mismatch", "sample_prefix"]);
+    assert_eq!(errors[1].error.code(), "FQ018");
+    assert_eq!(errors[1].error.line(), Some(5));
+    assert_eq!(errors[1].severity, Severity::Error);
+}
+
+#[test]
+fn custom_violations_are_counted_under_the_rule_name() {
+    let mut report = Vec::new();
+    let options = options(Validator::new().rule(SamplePrefix));
+    let summary = check_fastq_file(INPUT.as_bytes(), Some(&mut report), &options).unwrap();
+    assert_eq!(summary.records, 3);
+    assert_eq!(summary.errors, 1);
+    assert_eq!(summary.errors_by_kind.get("sample_prefix"), Some(&1));
+    let report = String::from_utf8(report).unwrap();
+    assert!(report.contains("other lacks the SAMPLE_ prefix"), "{}", report);
+    assert!(report.contains("@other\nACGT\n+\nIIII\n"), "{}", report);
+
+    let mut options = options;
+    options.severity.insert("sample_prefix", Severity::Warning);
+    let summary = check_fastq_file(INPUT.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!((summary.errors, summary.warnings), (0, 1));
+    assert_eq!(summary.warnings_by_kind.get("sample_prefix"), Some(&1));
+}
+
+#[test]
+fn empty_validator_only_applies_added_rules() {
+    let options = options(Validator::empty().rule(SamplePrefix));
+    let rec = FastqRecordRef { header: b"other", sequence: b"AC", plus_line: b"x", quality: b"I" };
+    let errors = validate_record_all(&rec, 1, &options);
+    assert_eq!(errors.iter().map(|v| v.error.kind()).collect::<Vec<_>>(), ["sample_prefix"]);
+}
+
+#[test]
+fn rules_can_be_removed_and_resolved_by_name() {
+    let validator = Validator::new().rule(SamplePrefix).without("length_mismatch");
+    assert!(!validator.rule_names().any(|name| name == "length_mismatch"));
+    assert_eq!(validator.rule_names().last(), Some("sample_prefix"));
+
+    let rec = FastqRecordRef { header: b"@SAMPLE_1", sequence: b"ACGT", plus_line: b"+", quality: b"II" };
+    assert!(validate_record_all(&rec, 1, &options(validator.clone())).is_empty());
+
+    assert_eq!(validator.resolve_kinds("sample_prefix"), Some(vec!["sample_prefix"]));
+    assert_eq!(validator.resolve_kinds("FQ005"), Some(vec!["length_mismatch"]));
+    assert_eq!(
+        validator.resolve_kinds("plus_line"),
+        Some(vec!["invalid_plus_line", "plus_line_mismatch", "plus_line_not_bare"])
+    );
+    assert_eq!(validator.resolve_kinds("no_such_rule"), None);
+}
+
+#[test]
+fn rules_choose_the_severity_of_each_violation() {
+    let input = "@other\nACGT\n+\nIIII\n@sample_2\nACGT\n+\nIIII\n";
+    let mut report = Vec::new();
+    let options = CheckOptions {
+        report_format: ReportFormat::Jsonl,
+        ..options(Validator::new().rule(SamplePrefix))
+    };
+    let summary = check_fastq_file(input.as_bytes(), Some(&mut report), &options).unwrap();
+    assert_eq!((summary.errors, summary.warnings), (1, 1));
+    assert_eq!(summary.errors_by_kind.get("sample_prefix"), Some(&1));
+    assert_eq!(summary.warnings_by_kind.get("sample_prefix"), Some(&1));
+    assert_eq!(summary.first_error.unwrap().line, Some(1));
+
+    let severities: Vec<String> = String::from_utf8(report)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["severity"].to_string())
+        .collect();
+    assert_eq!(severities, ["\"error\"", "\"warning\""]);
+
+    let rec = FastqRecordRef { header: b"@sample_2", sequence: b"ACGT", plus_line: b"+", quality: b"IIII" };
+    assert!(validate_record_with(&rec, 5, &options).is_ok());
+}
+
+#[test]
+fn configured_severity_overrides_the_rule() {
+    let mut options = options(Validator::new().rule(SamplePrefix));
+    options.severity.insert("sample_prefix", Severity::Error);
+    let rec = FastqRecordRef { header: b"@sample_2", sequence: b"ACGT", plus_line: b"+", quality: b"IIII" };
+    let errors = validate_record_all(&rec, 5, &options);
+    assert_eq!(errors[0].severity, Severity::Error);
+
+    options.severity.insert("sample_prefix", Severity::Off);
+    assert!(validate_record_all(&rec, 5, &options).is_empty());
+    let summary = check_fastq_file(INPUT.as_bytes(), None::<Vec<u8>>, &options).unwrap();
+    assert_eq!((summary.errors, summary.warnings), (0, 0));
+}
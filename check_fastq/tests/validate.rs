@@ -0,0 +1,123 @@
+use check_fastq::{
+    validate_record_all, validate_record_with, Alphabet, CheckOptions, FastqError, FastqRecordRef, Severity, Violation,
+};
+
+fn record<'a>([header, sequence, plus_line, quality]: [&'a str; 4]) -> FastqRecordRef<'a> {
+    FastqRecordRef {
+        header: header.as_bytes(),
+        sequence: sequence.as_bytes(),
+        plus_line: plus_line.as_bytes(),
+        quality: quality.as_bytes(),
+    }
+}
+
+#[test]
+fn rejects_bases_outside_the_alphabet() {
+    let rec = record(["@r1", "ACGRN", "+", "IIIII"]);
+    assert!(validate_record_with(&rec, 1, &CheckOptions::default()).is_ok());
+
+    let strict = CheckOptions { alphabet: Alphabet::Strict, ..CheckOptions::default() };
+    match validate_record_with(&rec, 1, &strict) {
+        Err(FastqError::InvalidBase { line, column, byte }) => assert_eq!((line, column, byte), (2, 4, b'R')),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let any = CheckOptions { alphabet: Alphabet::Any, ..CheckOptions::default() };
+    assert!(validate_record_all(&record(["@r1", "AC-*.", "+", "IIIII"]), 1, &any).is_empty());
+}
+
+#[test]
+fn rna_alphabet_rejects_thymine() {
+    let rna = CheckOptions { alphabet: Alphabet::Rna, ..CheckOptions::default() };
+    assert!(validate_record_with(&record(["@r1", "ACGUN", "+", "IIIII"]), 1, &rna).is_ok());
+    let errors = validate_record_all(&record(["@r1", "ACGTN", "+", "IIIII"]), 1, &rna);
+    assert!(matches!(errors[..], [Violation { error: FastqError::InvalidBase { column: 4, .. }, severity: Severity::Error }]));
+}
+
+#[test]
+fn lowercase_bases_are_invalid_only_when_case_sensitive() {
+    let rec = record(["@r1", "ACgt", "+", "IIII"]);
+    let errors = validate_record_all(&rec, 1, &CheckOptions::default());
+    assert!(matches!(errors[..], [Violation { error: FastqError::LowercaseBase { column: 3, .. }, severity: Severity::Warning }]));
+    assert!(validate_record_with(&rec, 1, &CheckOptions::default()).is_ok());
+
+    let sensitive = CheckOptions { case_sensitive: true, ..CheckOptions::default() };
+    assert!(matches!(
+        validate_record_with(&rec, 1, &sensitive),
+        Err(FastqError::InvalidBase { column: 3, byte: b'g', .. })
+    ));
+}
+
+#[test]
+fn plus_line_must_repeat_the_header() {
+    let options = CheckOptions::default();
+    assert!(validate_record_all(&record(["@r1 x", "ACGT", "+", "IIII"]), 1, &options).is_empty());
+
+    let errors = validate_record_all(&record(["@r1 x", "ACGT", "+r2 x", "IIII"]), 1, &options);
+    match &errors[..] {
+        [Violation { error: FastqError::PlusLineMismatch { line, header, plus }, .. }] => {
+            assert_eq!((*line, header.as_str(), plus.as_str()), (3, "r1 x", "r2 x"))
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let errors = validate_record_all(&record(["@r1", "ACGT", "-", "IIII"]), 1, &options);
+    assert!(matches!(errors[..], [Violation { error: FastqError::InvalidPlusLine(3), .. }]));
+}
+
+#[test]
+fn repeated_identifier_is_a_warning_unless_bare_plus_is_required() {
+    let rec = record(["@r1", "ACGT", "+r1", "IIII"]);
+    let errors = validate_record_all(&rec, 5, &CheckOptions::default());
+    assert!(matches!(
+        errors[..],
+        [Violation { error: FastqError::PlusLineNotBare(7), severity: Severity::Warning }]
+    ));
+    assert!(validate_record_with(&rec, 5, &CheckOptions::default()).is_ok());
+
+    let bare = CheckOptions { require_bare_plus: true, ..CheckOptions::default() };
+    assert!(matches!(validate_record_with(&rec, 5, &bare), Err(FastqError::PlusLineNotBare(7))));
+}
+
+#[test]
+fn validate_record_all_reports_every_violation_in_rule_order() {
+    let rec = record(["r1", "ACGTxa", "+r2", "II"]);
+    let errors = validate_record_all(&rec, 5, &CheckOptions::default());
+    let kinds: Vec<_> = errors.iter().map(|v| v.error.kind()).collect();
+    assert_eq!(kinds, ["invalid_header", "plus_line_not_bare", "length_mismatch", "invalid_base", "lowercase_base"]);
+    assert_eq!(errors.iter().map(|v| v.error.line()).collect::<Vec<_>>(), [5, 7, 8, 6, 6].map(Some));
+    let severities: Vec<_> = errors.iter().map(|v| v.severity).collect();
+    assert_eq!(severities, [Severity::Error, Severity::Warning, Severity::Error, Severity::Error, Severity::Warning]);
+
+    // 警告也会返回，validate_record_with 只返回第一个错误
+    let warning_only = record(["@r1", "ACgT", "+", "IIII"]);
+    assert!(matches!(
+        validate_record_all(&warning_only, 1, &CheckOptions::default())[..],
+        [Violation { error: FastqError::LowercaseBase { line: 2, column: 3, byte: b'g' }, .. }]
+    ));
+    assert!(validate_record_with(&warning_only, 1, &CheckOptions::default()).is_ok());
+    assert!(matches!(
+        validate_record_with(&rec, 5, &CheckOptions::default()),
+        Err(FastqError::InvalidHeader(5))
+    ));
+}
+
+#[test]
+fn validate_record_all_is_empty_for_a_valid_record() {
+    let rec = record(["@r1 1:N:0:ACGT", "ACGTN", "+", "IIII#"]);
+    assert!(validate_record_all(&rec, 1, &CheckOptions::default()).is_empty());
+}
+
+#[test]
+fn configured_severity_applies_to_each_violation() {
+    let mut options = CheckOptions::default();
+    options.severity.insert("length_mismatch", Severity::Warning);
+    options.severity.insert("invalid_header", Severity::Off);
+    let rec = record(["r1", "ACGT", "+", "II"]);
+    let errors = validate_record_all(&rec, 1, &options);
+    assert!(matches!(
+        errors[..],
+        [Violation { error: FastqError::LengthMismatch { .. }, severity: Severity::Warning }]
+    ));
+    assert!(validate_record_with(&rec, 1, &options).is_ok());
+}